@@ -1,7 +1,43 @@
+use serde::{Deserialize, Serialize};
+use solana_clock::Slot;
+use solana_hash::Hash;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use thiserror::Error;
 
 pub type DirByteLimit = u64;
 
+/// An event recorded in the banking trace, serialized with bincode.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum BankingTraceEvent {
+    /// A batch of packets received by the banking stage.
+    PacketBatch {
+        timestamp: SystemTime,
+        packets: Vec<Vec<u8>>,
+    },
+    /// The block and bank hashes of a frozen bank.
+    BlockAndBankHash {
+        slot: Slot,
+        blockhash: Hash,
+        bankhash: Hash,
+    },
+}
+
+impl BankingTraceEvent {
+    /// Returns the size of the serialized event, so that the trace writer can
+    /// keep the trace directory within its [`DirByteLimit`].
+    pub fn serialized_size(&self) -> Result<u64, TraceError> {
+        Ok(bincode::serialized_size(self)?)
+    }
+}
+
+/// Creates the trace directory, along with any missing parents, before the
+/// trace writer starts writing to it.
+pub fn create_trace_dir(path: &Path) -> Result<(), TraceError> {
+    fs::create_dir_all(path).map_err(|err| TraceError::CreateDirFailed(path.to_path_buf(), err))
+}
+
 #[derive(Error, Debug)]
 pub enum TraceError {
     #[error("IO Error: {0}")]
@@ -15,4 +51,67 @@ pub enum TraceError {
 
     #[error("Trace directory's byte limit is too small (must be larger than {1}): {0}")]
     TooSmallDirByteLimit(DirByteLimit, DirByteLimit),
+
+    #[error("Failed to create trace directory {0:?}: {1}")]
+    CreateDirFailed(PathBuf, std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn serialize_roundtrip(event: &BankingTraceEvent) -> usize {
+        let bytes = bincode::serialize(event).unwrap();
+        assert_eq!(
+            &bincode::deserialize::<BankingTraceEvent>(&bytes).unwrap(),
+            event
+        );
+        bytes.len()
+    }
+
+    #[test]
+    fn test_packet_batch_serialization() {
+        let event = BankingTraceEvent::PacketBatch {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(1_234),
+            packets: vec![vec![1, 2, 3], vec![], vec![4; 100]],
+        };
+        let len = serialize_roundtrip(&event);
+        assert_eq!(event.serialized_size().unwrap(), len as u64);
+        // variant tag, timestamp seconds and nanoseconds, the number of
+        // packets, then the length and bytes of each packet
+        assert_eq!(len, 4 + 8 + 4 + 8 + (8 + 3) + 8 + (8 + 100));
+    }
+
+    #[test]
+    fn test_block_and_bank_hash_serialization() {
+        let event = BankingTraceEvent::BlockAndBankHash {
+            slot: 42,
+            blockhash: Hash::new_unique(),
+            bankhash: Hash::new_unique(),
+        };
+        let len = serialize_roundtrip(&event);
+        assert_eq!(event.serialized_size().unwrap(), len as u64);
+        assert_eq!(len, 4 + 8 + 32 + 32);
+    }
+
+    #[test]
+    fn test_create_trace_dir() {
+        let tmp_dir = TempDir::new().unwrap();
+        let trace_dir = tmp_dir.path().join("banking_trace").join("nested");
+        create_trace_dir(&trace_dir).unwrap();
+        assert!(trace_dir.is_dir());
+        // An existing directory is fine
+        create_trace_dir(&trace_dir).unwrap();
+
+        // The directory can't be created under a regular file
+        let not_a_dir = tmp_dir.path().join("not-a-dir");
+        fs::write(&not_a_dir, "").unwrap();
+        let trace_dir = not_a_dir.join("banking_trace");
+        assert!(matches!(
+            create_trace_dir(&trace_dir),
+            Err(TraceError::CreateDirFailed(path, _)) if path == trace_dir
+        ));
+    }
 }
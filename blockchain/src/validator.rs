@@ -3,7 +3,9 @@ use anyhow::{anyhow, Result};
 use blockchain_accounts_db::hardened_unpack::{open_genesis_config, OpenGenesisConfigError};
 use blockchain_gossip::node::Node;
 use blockchain_ledger::blockstore::error::BlockstoreError;
-use log::info;
+use blockchain_ledger::blockstore::Blockstore;
+use blockchain_ledger::blockstore_options::BlockstoreOptions;
+use log::{info, warn};
 use solana_clock::Slot;
 use solana_epoch_schedule::MAX_LEADER_SCHEDULE_EPOCH_OFFSET;
 use solana_genesis_config::GenesisConfig;
@@ -54,6 +56,19 @@ impl Validator {
 
         let _genesis_config = load_genesis(config, ledger_path)?;
 
+        if config.check_ledger {
+            check_ledger(ledger_path, config.blockstore_options.clone())?;
+        }
+
+        if let Some((slot, expected_bank_hash)) = config.required_bank_hash {
+            check_bank_hash(
+                ledger_path,
+                config.blockstore_options.clone(),
+                slot,
+                expected_bank_hash,
+            )?;
+        }
+
         Ok(Self {})
     }
 }
@@ -72,6 +87,12 @@ pub struct ValidatorConfig {
 
     pub rpc_addrs: Option<(SocketAddr, SocketAddr)>,
 
+    /// When set to `true`, the full RPC API is enabled, rather than only the
+    /// methods needed to operate the cluster.
+    ///
+    /// Only records the intent for now, there is no RPC server yet.
+    pub full_rpc_api: bool,
+
     /// When set to `true`, the validator will require a tower for voting.
     ///
     /// This refers to the Tower BFT consensus algorithm, a data structure that helps a
@@ -80,6 +101,17 @@ pub struct ValidatorConfig {
     pub require_tower: bool,
 
     pub max_genesis_archive_unpacked_size: u64,
+
+    /// When set to `true`, the validator checks the blockstore for inconsistent
+    /// slot metadata on startup and logs any it finds.
+    pub check_ledger: bool,
+
+    pub blockstore_options: BlockstoreOptions,
+
+    /// When set, the validator refuses to start unless the blockstore holds
+    /// this bank hash for the slot, guarding against booting on a corrupted
+    /// or forked ledger.
+    pub required_bank_hash: Option<(Slot, Hash)>,
 }
 
 fn load_genesis(
@@ -111,6 +143,51 @@ fn load_genesis(
     Ok(genesis_config)
 }
 
+fn check_ledger(
+    ledger_path: &Path,
+    blockstore_options: BlockstoreOptions,
+) -> Result<(), ValidatorError> {
+    let blockstore = Blockstore::open_with_options(ledger_path, blockstore_options)
+        .map_err(ValidatorError::Blockstore)?;
+    let inconsistencies = blockstore
+        .run_consistency_check()
+        .map_err(ValidatorError::Blockstore)?;
+    for inconsistency in &inconsistencies {
+        warn!("ledger inconsistency: {inconsistency:?}");
+    }
+    info!(
+        "ledger check found {} inconsistent slot(s)",
+        inconsistencies.len()
+    );
+    Ok(())
+}
+
+fn check_bank_hash(
+    ledger_path: &Path,
+    blockstore_options: BlockstoreOptions,
+    slot: Slot,
+    expected_bank_hash: Hash,
+) -> Result<(), ValidatorError> {
+    let blockstore = Blockstore::open_with_options(ledger_path, blockstore_options)
+        .map_err(ValidatorError::Blockstore)?;
+    let Some(bank_hash) = blockstore
+        .get_bank_hash(slot)
+        .map_err(ValidatorError::Blockstore)?
+    else {
+        return Err(ValidatorError::Other(format!(
+            "no bank hash stored for slot {slot}, expected {expected_bank_hash}"
+        )));
+    };
+    if bank_hash != expected_bank_hash {
+        return Err(ValidatorError::BankHashMismatch(
+            bank_hash,
+            expected_bank_hash,
+        ));
+    }
+    info!("bank hash of slot {slot} matches {expected_bank_hash}");
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum ValidatorError {
     #[error("bank hash mismatch: actual={0}, expected={1}")]
@@ -148,3 +225,48 @@ pub enum ValidatorError {
     #[error("Wen Restart finished, please continue with --wait-for-supermajority")]
     WenRestartFinished,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_bank_hash() {
+        let ledger_path = TempDir::new().unwrap();
+        let bank_hash = Hash::new_unique();
+        Blockstore::open(ledger_path.path())
+            .unwrap()
+            .insert_bank_hash(5, bank_hash, false)
+            .unwrap();
+
+        check_bank_hash(
+            ledger_path.path(),
+            BlockstoreOptions::default(),
+            5,
+            bank_hash,
+        )
+        .unwrap();
+
+        let expected_bank_hash = Hash::new_unique();
+        assert!(matches!(
+            check_bank_hash(
+                ledger_path.path(),
+                BlockstoreOptions::default(),
+                5,
+                expected_bank_hash
+            ),
+            Err(ValidatorError::BankHashMismatch(actual, expected))
+                if actual == bank_hash && expected == expected_bank_hash
+        ));
+        assert!(matches!(
+            check_bank_hash(
+                ledger_path.path(),
+                BlockstoreOptions::default(),
+                6,
+                bank_hash
+            ),
+            Err(ValidatorError::Other(_))
+        ));
+    }
+}
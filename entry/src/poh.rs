@@ -1,4 +1,6 @@
 use log::info;
+use solana_clock::DEFAULT_HASHES_PER_TICK;
+use solana_cluster_type::ClusterType;
 use solana_hash::Hash;
 use solana_sha256_hasher::{hash, hashv};
 use std::time::{Duration, Instant};
@@ -90,6 +92,26 @@ impl Poh {
             hash: self.hash,
         })
     }
+
+    /// Hashes and ticks until `target_ticks` ticks have been produced,
+    /// returning the tick entries.
+    ///
+    /// In low power mode ticks are paced by the caller rather than by hashing,
+    /// so at most one tick is produced per call.
+    pub fn hash_and_tick_to(&mut self, target_ticks: u64) -> Vec<PohEntry> {
+        let mut entries = vec![];
+        if self.hashes_per_tick == LOW_POWER_MODE {
+            if self.tick_number < target_ticks {
+                entries.extend(self.tick());
+            }
+            return entries;
+        }
+        while self.tick_number < target_ticks {
+            self.hash(u64::MAX);
+            entries.extend(self.tick());
+        }
+        entries
+    }
 }
 
 pub fn compute_hash_time(hashes_sample_size: u64) -> Duration {
@@ -106,3 +128,107 @@ pub fn compute_hashes_per_tick(duration: Duration, hashes_sample_size: u64) -> u
     let elapsed_ms = compute_hash_time(hashes_sample_size).as_millis() as u64;
     duration.as_millis() as u64 * hashes_sample_size / elapsed_ms
 }
+
+/// Resolves the `hashes_per_tick` of a `PohConfig` from `mode`, which is one of:
+///
+/// * `auto`: 50% of this machine's peak hash rate for development clusters,
+///   [`DEFAULT_HASHES_PER_TICK`] for all other cluster types
+/// * `sleep`: `None`, ticks are produced by sleeping for `target_tick_duration`
+/// * an explicit number of hashes per tick
+pub fn resolve_hashes_per_tick(
+    mode: &str,
+    target_tick_duration: Duration,
+    cluster_type: ClusterType,
+) -> Result<Option<u64>, String> {
+    match mode {
+        "auto" => match cluster_type {
+            ClusterType::Development => {
+                let hashes_per_tick = compute_hashes_per_tick(target_tick_duration, 1_000_000);
+                Ok(Some(hashes_per_tick / 2)) // use 50% of peak ability
+            }
+            ClusterType::Devnet | ClusterType::Testnet | ClusterType::MainnetBeta => {
+                Ok(Some(DEFAULT_HASHES_PER_TICK))
+            }
+        },
+        "sleep" => Ok(None),
+        s => s
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|err| format!("invalid value for hashes per tick: {s}: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGET_TICK_DURATION: Duration = Duration::from_millis(6);
+
+    #[test]
+    fn test_hash_and_tick_to() {
+        let mut poh = Poh::new(Hash::default(), Some(10));
+        let entries = poh.hash_and_tick_to(3);
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|entry| entry.num_hashes == 10));
+        assert_eq!(entries.last().unwrap().hash, poh.hash);
+        assert_eq!(poh.tick_number, 3);
+
+        // a partially hashed tick is completed
+        assert!(poh.record(Hash::new_unique()).is_some());
+        let entries = poh.hash_and_tick_to(4);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].num_hashes, 9);
+
+        // already at the target
+        assert!(poh.hash_and_tick_to(4).is_empty());
+    }
+
+    #[test]
+    fn test_hash_and_tick_to_low_power_mode() {
+        let mut poh = Poh::new(Hash::default(), None);
+        let entries = poh.hash_and_tick_to(3);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].num_hashes, 1);
+        assert_eq!(poh.tick_number, 1);
+
+        assert_eq!(poh.hash_and_tick_to(3).len(), 1);
+        assert_eq!(poh.hash_and_tick_to(3).len(), 1);
+        assert!(poh.hash_and_tick_to(3).is_empty());
+        assert_eq!(poh.tick_number, 3);
+    }
+
+    #[test]
+    fn test_resolve_hashes_per_tick_auto_development() {
+        let hashes_per_tick =
+            resolve_hashes_per_tick("auto", TARGET_TICK_DURATION, ClusterType::Development)
+                .unwrap();
+        assert!(hashes_per_tick.unwrap() > 0);
+    }
+
+    #[test]
+    fn test_resolve_hashes_per_tick_auto_mainnet() {
+        assert_eq!(
+            resolve_hashes_per_tick("auto", TARGET_TICK_DURATION, ClusterType::MainnetBeta),
+            Ok(Some(DEFAULT_HASHES_PER_TICK))
+        );
+    }
+
+    #[test]
+    fn test_resolve_hashes_per_tick_sleep() {
+        assert_eq!(
+            resolve_hashes_per_tick("sleep", TARGET_TICK_DURATION, ClusterType::Development),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_resolve_hashes_per_tick_explicit() {
+        assert_eq!(
+            resolve_hashes_per_tick("12500", TARGET_TICK_DURATION, ClusterType::Testnet),
+            Ok(Some(12_500))
+        );
+        assert!(
+            resolve_hashes_per_tick("fast", TARGET_TICK_DURATION, ClusterType::Testnet).is_err()
+        );
+    }
+}
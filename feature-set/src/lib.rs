@@ -95,6 +95,37 @@ impl FeatureSet {
         }
     }
 
+    /// Number of active features
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Number of inactive features
+    pub fn inactive_count(&self) -> usize {
+        self.inactive.len()
+    }
+
+    /// Fraction of known features that are active, in `[0.0, 1.0]`
+    pub fn activation_ratio(&self) -> f64 {
+        let total = self.active_count().saturating_add(self.inactive_count());
+        if total == 0 {
+            return 0.0;
+        }
+        self.active_count() as f64 / total as f64
+    }
+
+    /// Returns the feature set as it was at `slot`: features activated after
+    /// `slot` are moved back to the inactive set
+    pub fn active_at_slot(&self, slot: u64) -> Self {
+        let mut feature_set = self.clone();
+        for (feature_id, activation_slot) in self.active.iter() {
+            if *activation_slot > slot {
+                feature_set.deactivate(feature_id);
+            }
+        }
+        feature_set
+    }
+
     pub fn new_warmup_cooldown_rate_epoch(&self, epoch_schedule: &EpochSchedule) -> Option<u64> {
         self.activated_slot(&reduce_stake_warmup_cooldown::id())
             .map(|slot| epoch_schedule.get_epoch(slot))
@@ -1462,4 +1493,49 @@ mod test {
                 .collect()
         );
     }
+
+    #[test]
+    fn test_active_at_slot() {
+        let mut feature_set = FeatureSet::default();
+        feature_set.activate(&pico_inflation::id(), 10);
+        feature_set.activate(&secp256k1_program_enabled::id(), 20);
+
+        let at_15 = feature_set.active_at_slot(15);
+        assert_eq!(at_15.activated_slot(&pico_inflation::id()), Some(10));
+        assert!(!at_15.is_active(&secp256k1_program_enabled::id()));
+        assert!(at_15.inactive().contains(&secp256k1_program_enabled::id()));
+        assert_eq!(
+            at_15.active().len() + at_15.inactive().len(),
+            feature_set.active().len() + feature_set.inactive().len()
+        );
+
+        // A feature is active at its activation slot
+        assert!(feature_set
+            .active_at_slot(20)
+            .is_active(&secp256k1_program_enabled::id()));
+        assert!(feature_set.active_at_slot(9).active().is_empty());
+        assert_eq!(feature_set.active_at_slot(u64::MAX), feature_set);
+    }
+
+    #[test]
+    fn test_activation_counts() {
+        let mut feature_set = FeatureSet::default();
+        assert_eq!(feature_set.active_count(), 0);
+        assert_eq!(feature_set.inactive_count(), FEATURE_NAMES.len());
+        assert_eq!(feature_set.activation_ratio(), 0.0);
+
+        feature_set.activate(&pico_inflation::id(), 10);
+        feature_set.activate(&secp256k1_program_enabled::id(), 20);
+        assert_eq!(feature_set.active_count(), 2);
+        assert_eq!(feature_set.inactive_count(), FEATURE_NAMES.len() - 2);
+        assert_eq!(
+            feature_set.activation_ratio(),
+            2.0 / FEATURE_NAMES.len() as f64
+        );
+
+        let feature_set = FeatureSet::all_enabled();
+        assert_eq!(feature_set.active_count(), FEATURE_NAMES.len());
+        assert_eq!(feature_set.inactive_count(), 0);
+        assert_eq!(feature_set.activation_ratio(), 1.0);
+    }
 }
@@ -2,13 +2,17 @@ use blockchain_accounts_db::hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE;
 use blockchain_clap_utils::input_parsers::{
     parse_percentage, parse_pubkey, parse_slot, unix_timestamp_from_rfc3339_datetime,
 };
-use blockchain_entry::poh::compute_hashes_per_tick;
+use blockchain_entry::poh::resolve_hashes_per_tick;
 use blockchain_ledger::blockstore::create_new_ledger;
 use blockchain_ledger::blockstore_options::LedgerColumnOptions;
+use blockchain_runtime::genesis_utils::total_lamports;
 use blockchain_stake_program::{add_genesis_accounts, stake_state};
 use blockchain_vote_program::vote_state;
-use clap::{crate_description, crate_name, crate_version, Arg, ArgAction, Command};
-use solana_account::AccountSharedData;
+use clap::parser::ValueSource;
+use clap::{crate_description, crate_name, crate_version, Arg, ArgAction, ArgGroup, Command};
+use serde_derive::Serialize;
+use solana_account::state_traits::StateMut;
+use solana_account::{Account, AccountSharedData};
 use solana_clock as clock;
 use solana_clock::{Slot, UnixTimestamp};
 use solana_cluster_type::ClusterType;
@@ -23,7 +27,9 @@ use solana_rent::Rent;
 use solana_sdk_ids::system_program;
 use solana_stake_interface::state::StakeStateV2;
 use solana_vote_interface::state::VoteStateV3;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 use std::slice::Iter;
 use std::time::Duration;
 use std::{io, process};
@@ -132,13 +138,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .value_parser(clap::value_parser!(u64))
                 .help("Number of lamports to assign to the faucet"),
         )
+        .arg(
+            Arg::new("faucet_percentage")
+                .long("faucet-percentage")
+                .value_name("NUMBER")
+                .value_parser(parse_percentage)
+                .help(
+                    "Fund the faucet with this percentage of the lamports allocated to the \
+                     bootstrap validators",
+                ),
+        )
+        .group(ArgGroup::new("faucet_funding").args(["faucet_lamports", "faucet_percentage"]))
         .arg(
             Arg::new("faucet_pubkey")
                 .short('m')
                 .long("faucet-pubkey")
                 .value_name("PUBKEY")
                 .value_parser(parse_pubkey)
-                .requires("faucet_lamports")
+                .requires("faucet_funding")
                 .default_value(default_faucet_pubkey)
                 .help("Path to file containing the faucet's pubkey"),
         )
@@ -152,6 +169,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                      validator's stake [default: --bootstrap-validator IDENTITY_PUBKEY]",
                 ),
         )
+        .arg(
+            Arg::new("bootstrap_validator_authorized_withdrawer")
+                .long("bootstrap-validator-authorized-withdrawer")
+                .value_name("PUBKEY")
+                .value_parser(parse_pubkey)
+                .help(
+                    "Pubkey authorized to withdraw from the bootstrap validator's vote account \
+                     [default: --bootstrap-validator IDENTITY_PUBKEY]",
+                ),
+        )
         .arg(
             Arg::new("bootstrap_validator_lamports")
                 .long("bootstrap-validator-lamports")
@@ -179,6 +206,26 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                      verification when the cluster is operating at target-signatures-per-slot",
                 ),
         )
+        .arg(
+            Arg::new("min_lamports_per_signature")
+                .long("min-lamports-per-signature")
+                .value_name("LAMPORTS")
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "The lowest cost in lamports that the cluster will charge for signature \
+                     verification [default: half of --target-lamports-per-signature]",
+                ),
+        )
+        .arg(
+            Arg::new("max_lamports_per_signature")
+                .long("max-lamports-per-signature")
+                .value_name("LAMPORTS")
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "The highest cost in lamports that the cluster will charge for signature \
+                     verification [default: 10 times --target-lamports-per-signature]",
+                ),
+        )
         .arg(
             Arg::new("lamports_per_byte_year")
                 .long("lamports-per-byte-year")
@@ -275,10 +322,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .arg(
             Arg::new("enable_warmup_epochs")
                 .long("enable-warmup-epochs")
-                .action(ArgAction::SetTrue)
+                .value_name("BOOL")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("true")
+                .value_parser(clap::value_parser!(bool))
                 .help(
                     "When enabled epochs start short and will grow. \
-                     Useful for warming up stake quickly during development",
+                     Useful for warming up stake quickly during development \
+                     [default: true for the development cluster type, false otherwise]",
                 ),
         )
         .arg(
@@ -311,12 +363,46 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .value_parser(clap::value_parser!(u64))
                 .help("maximum total uncompressed file size of created genesis archive"),
         )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("FORMAT")
+                .value_parser(["json", "yaml"])
+                .help(
+                    "Print the generated genesis config in the given machine-readable format \
+                     instead of the human-readable summary",
+                ),
+        )
         .arg(
             Arg::new("inflation")
                 .long("inflation")
                 .value_parser(["pico", "full", "none"])
                 .help("Selects inflation"),
         )
+        .arg(
+            Arg::new("inflation_initial")
+                .long("inflation-initial")
+                .value_name("RATE")
+                .value_parser(clap::value_parser!(f64))
+                .help("Initial inflation rate, overriding the one selected by --inflation"),
+        )
+        .arg(
+            Arg::new("inflation_terminal")
+                .long("inflation-terminal")
+                .value_name("RATE")
+                .value_parser(clap::value_parser!(f64))
+                .help("Terminal inflation rate, overriding the one selected by --inflation"),
+        )
+        .arg(
+            Arg::new("inflation_taper")
+                .long("inflation-taper")
+                .value_name("RATE")
+                .value_parser(clap::value_parser!(f64))
+                .help(
+                    "Rate per year at which inflation is lowered until reaching the terminal \
+                     rate, overriding the one selected by --inflation",
+                ),
+        )
         .try_get_matches()
         .unwrap_or_else(|e| {
             eprintln!("failed to parse args: {}", e);
@@ -373,10 +459,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let bootstrap_stake_authorized_pubkey = matches
         .try_get_one::<Pubkey>("bootstrap_stake_authorized_pubkey")?
         .copied();
-    let faucet_lamports = matches
-        .try_get_one::<u64>("faucet_lamports")?
-        .copied()
-        .unwrap_or(0);
+    let bootstrap_validator_authorized_withdrawer = matches
+        .try_get_one::<Pubkey>("bootstrap_validator_authorized_withdrawer")?
+        .copied();
+    let faucet_lamports = matches.try_get_one::<u64>("faucet_lamports")?.copied();
+    let faucet_percentage = matches.try_get_one::<u8>("faucet_percentage")?.copied();
     let faucet_pubkey = matches.try_get_one::<Pubkey>("faucet_pubkey")?.copied();
 
     // can use unwrap as we provided a default value.
@@ -399,6 +486,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .try_get_one::<u8>("fee_burn_percentage")?
         .copied()
         .unwrap();
+    set_fee_bounds(
+        &mut fee_rate_governor,
+        matches
+            .try_get_one::<u64>("min_lamports_per_signature")?
+            .copied(),
+        matches
+            .try_get_one::<u64>("max_lamports_per_signature")?
+            .copied(),
+    )?;
 
     let mut poh_config = PohConfig {
         target_tick_duration: match matches.try_get_one::<u64>("target_tick_duration")? {
@@ -420,31 +516,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     //         std::process::exit(1);
     //     });
 
-    match matches
-        .try_get_one::<String>("hashes_per_tick")?
-        .unwrap()
-        .as_str()
-    {
-        "auto" => match cluster_type {
-            ClusterType::Development => {
-                let hashes_per_tick =
-                    compute_hashes_per_tick(poh_config.target_tick_duration, 1_000_000);
-                poh_config.hashes_per_tick = Some(hashes_per_tick / 2); // use 50% of peak ability
-            }
-            ClusterType::Devnet | ClusterType::Testnet | ClusterType::MainnetBeta => {
-                poh_config.hashes_per_tick = Some(clock::DEFAULT_HASHES_PER_TICK);
-            }
-        },
-        "sleep" => {
-            poh_config.hashes_per_tick = None;
-        }
-        s => {
-            poh_config.hashes_per_tick = Some(s.parse::<u64>().unwrap_or_else(|err| {
-                eprintln!("Error: invalid value for --hashes-per-tick: {s}: {err}");
-                process::exit(1);
-            }));
-        }
-    }
+    poh_config.hashes_per_tick = resolve_hashes_per_tick(
+        matches.try_get_one::<String>("hashes_per_tick")?.unwrap(),
+        poh_config.target_tick_duration,
+        cluster_type,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    });
 
     let slots_per_epoch = match matches.try_get_one::<Slot>("slots_per_epoch")? {
         None => match cluster_type {
@@ -458,7 +538,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let epoch_schedule = EpochSchedule::custom(
         slots_per_epoch,
         slots_per_epoch,
-        matches.get_flag("enable_warmup_epochs"),
+        enable_warmup_epochs(
+            (matches.value_source("enable_warmup_epochs") == Some(ValueSource::CommandLine))
+                .then(|| matches.get_one::<bool>("enable_warmup_epochs").copied())
+                .flatten(),
+            cluster_type,
+        ),
     );
 
     let mut genesis_config = GenesisConfig {
@@ -481,6 +566,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         };
         genesis_config.inflation = inflation;
     }
+    genesis_config.inflation = custom_inflation(
+        genesis_config.inflation,
+        matches.try_get_one::<f64>("inflation_initial")?.copied(),
+        matches.try_get_one::<f64>("inflation_terminal")?.copied(),
+        matches.try_get_one::<f64>("inflation_taper")?.copied(),
+    )?;
 
     let commission = matches
         .try_get_one::<u8>("vote_commission_percentage")?
@@ -496,7 +587,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         commission,
         &rent,
         bootstrap_stake_authorized_pubkey.as_ref(),
+        bootstrap_validator_authorized_withdrawer.as_ref(),
     )?;
+    check_bootstrap_delegations(&genesis_config, &bootstrap_validator_pubkeys)?;
 
     if let Some(creation_time) = matches
         .try_get_one::<UnixTimestamp>("creation_time")?
@@ -505,6 +598,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         genesis_config.creation_time = creation_time;
     }
 
+    let bootstrap_validators_lamports = total_lamports(&genesis_config);
+    let faucet_lamports = compute_faucet_lamports(
+        faucet_lamports,
+        faucet_percentage,
+        bootstrap_validators_lamports,
+    )?;
     if let Some(faucet_pubkey) = faucet_pubkey {
         genesis_config.add_account(
             faucet_pubkey,
@@ -538,11 +637,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .copied()
         .unwrap();
 
-    let issued_lamports = genesis_config
-        .accounts
-        .values()
-        .map(|account| account.lamports)
-        .sum::<u64>();
+    let issued_lamports = total_lamports(&genesis_config);
 
     // skip for development clusters
     // add_genesis_accounts(&mut genesis_config, issued_lamports - faucet_lamports);
@@ -646,11 +741,85 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_genesis_archive_unpacked_size,
         LedgerColumnOptions::default(),
     )?;
+    verify_written_genesis_config(&ledger_path, &genesis_config)?;
 
-    println!("{genesis_config}");
+    match matches.get_one::<String>("output").map(String::as_str) {
+        Some(format) => println!("{}", format_genesis_config(&genesis_config, format)?),
+        None => println!("{genesis_config}"),
+    }
+    // stderr, so that `--output` stays machine-readable
+    eprintln!("Total lamports: {issued_lamports}");
     Ok(())
 }
 
+/// Serializable view of a [`GenesisConfig`] for `--output`.
+///
+/// Pubkeys are rendered as base58 strings, as JSON only allows string map keys.
+#[derive(Serialize)]
+struct GenesisConfigOutput<'a> {
+    hash: String,
+    creation_time: UnixTimestamp,
+    total_lamports: u64,
+    accounts: BTreeMap<String, &'a Account>,
+    native_instruction_processors: Vec<(&'a str, String)>,
+    rewards_pools: BTreeMap<String, &'a Account>,
+    ticks_per_slot: u64,
+    poh_config: &'a PohConfig,
+    fee_rate_governor: &'a FeeRateGovernor,
+    rent: &'a Rent,
+    inflation: &'a Inflation,
+    epoch_schedule: &'a EpochSchedule,
+    cluster_type: ClusterType,
+}
+
+impl<'a> From<&'a GenesisConfig> for GenesisConfigOutput<'a> {
+    fn from(genesis_config: &'a GenesisConfig) -> Self {
+        let accounts = |accounts: &'a BTreeMap<Pubkey, Account>| {
+            accounts
+                .iter()
+                .map(|(pubkey, account)| (pubkey.to_string(), account))
+                .collect()
+        };
+        Self {
+            hash: genesis_config.hash().to_string(),
+            creation_time: genesis_config.creation_time,
+            total_lamports: total_lamports(genesis_config),
+            accounts: accounts(&genesis_config.accounts),
+            native_instruction_processors: genesis_config
+                .native_instruction_processors
+                .iter()
+                .map(|(name, program_id)| (name.as_str(), program_id.to_string()))
+                .collect(),
+            rewards_pools: accounts(&genesis_config.rewards_pools),
+            ticks_per_slot: genesis_config.ticks_per_slot,
+            poh_config: &genesis_config.poh_config,
+            fee_rate_governor: &genesis_config.fee_rate_governor,
+            rent: &genesis_config.rent,
+            inflation: &genesis_config.inflation,
+            epoch_schedule: &genesis_config.epoch_schedule,
+            cluster_type: genesis_config.cluster_type,
+        }
+    }
+}
+
+/// Serializes `genesis_config` as `json` or `yaml`.
+fn format_genesis_config(genesis_config: &GenesisConfig, format: &str) -> io::Result<String> {
+    let output = GenesisConfigOutput::from(genesis_config);
+    match format {
+        "json" => serde_json::to_string_pretty(&output).map_err(io::Error::other),
+        // Account data is serialized as bytes, which YAML doesn't support, so
+        // go through a JSON value first
+        "yaml" => serde_json::to_value(&output)
+            .map_err(io::Error::other)
+            .and_then(|value| serde_yaml_ng::to_string(&value).map_err(io::Error::other)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported output format: {format}"),
+        )),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn add_validator_accounts(
     genesis_config: &mut GenesisConfig,
     pubkeys_iter: &mut Iter<Pubkey>,
@@ -659,6 +828,7 @@ fn add_validator_accounts(
     commission: u8,
     rent: &Rent,
     authorized_pubkey: Option<&Pubkey>,
+    authorized_withdrawer: Option<&Pubkey>,
 ) -> io::Result<()> {
     rent_exempt_check(
         stake_lamports,
@@ -680,7 +850,7 @@ fn add_validator_accounts(
         let vote_account = vote_state::create_account_with_authorized(
             identity_pubkey,
             identity_pubkey,
-            identity_pubkey,
+            authorized_withdrawer.unwrap_or(identity_pubkey),
             commission,
             VoteStateV3::get_rent_exempt_reserve(rent).max(1),
         );
@@ -700,6 +870,72 @@ fn add_validator_accounts(
     Ok(())
 }
 
+/// Verifies that the stake account of each `identity, vote, stake` triple in
+/// `pubkeys` delegates to the vote account of the same triple.
+fn check_bootstrap_delegations(
+    genesis_config: &GenesisConfig,
+    pubkeys: &[Pubkey],
+) -> io::Result<()> {
+    for triple in pubkeys.chunks_exact(3) {
+        let (vote_pubkey, stake_pubkey) = (&triple[1], &triple[2]);
+        let voter_pubkey = genesis_config
+            .accounts
+            .get(stake_pubkey)
+            .and_then(|stake_account| stake_account.state().ok())
+            .and_then(|stake_state: StakeStateV2| stake_state.delegation())
+            .map(|delegation| delegation.voter_pubkey);
+        if voter_pubkey != Some(*vote_pubkey) {
+            return Err(io::Error::other(format!(
+                "error: bootstrap stake account {stake_pubkey} does not delegate to vote account \
+                 {vote_pubkey}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Reloads the genesis config written to `ledger_path` and checks that its fee
+/// rate governor, rent and epoch schedule are the ones of `genesis_config`.
+fn verify_written_genesis_config(
+    ledger_path: &Path,
+    genesis_config: &GenesisConfig,
+) -> io::Result<()> {
+    fn check<T: PartialEq + Debug>(name: &str, written: &T, expected: &T) -> io::Result<()> {
+        if written == expected {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "error: written genesis config has {name} {written:?}, expected {expected:?}"
+            )))
+        }
+    }
+
+    let written = GenesisConfig::load(ledger_path)?;
+    // lamports_per_signature isn't serialized, it is derived from the target
+    // when the cluster starts
+    let fee_rate_governor = FeeRateGovernor {
+        lamports_per_signature: written.fee_rate_governor.lamports_per_signature,
+        ..genesis_config.fee_rate_governor.clone()
+    };
+    check(
+        "fee rate governor",
+        &written.fee_rate_governor,
+        &fee_rate_governor,
+    )?;
+    check("rent", &written.rent, &genesis_config.rent)?;
+    check(
+        "epoch schedule",
+        &written.epoch_schedule,
+        &genesis_config.epoch_schedule,
+    )
+}
+
+/// Whether warmup epochs are enabled: an explicit `--enable-warmup-epochs` value
+/// wins, otherwise they are on for development clusters only.
+fn enable_warmup_epochs(explicit: Option<bool>, cluster_type: ClusterType) -> bool {
+    explicit.unwrap_or(cluster_type == ClusterType::Development)
+}
+
 fn rent_exempt_check(stake_lamports: u64, exempt: u64) -> io::Result<()> {
     if stake_lamports < exempt {
         Err(io::Error::other(
@@ -711,3 +947,329 @@ fn rent_exempt_check(stake_lamports: u64, exempt: u64) -> io::Result<()> {
         Ok(())
     }
 }
+
+/// Returns the lamports to fund the faucet with: either `faucet_lamports`, or
+/// `faucet_percentage` percent of `bootstrap_validators_lamports`.
+fn compute_faucet_lamports(
+    faucet_lamports: Option<u64>,
+    faucet_percentage: Option<u8>,
+    bootstrap_validators_lamports: u64,
+) -> io::Result<u64> {
+    match (faucet_lamports, faucet_percentage) {
+        (Some(_), Some(_)) => Err(io::Error::other(
+            "error: --faucet-lamports and --faucet-percentage cannot be used together",
+        )),
+        (Some(faucet_lamports), None) => Ok(faucet_lamports),
+        (None, Some(faucet_percentage)) => Ok((u128::from(bootstrap_validators_lamports)
+            * u128::from(faucet_percentage)
+            / 100) as u64),
+        (None, None) => Ok(0),
+    }
+}
+
+/// Overrides the lamports per signature bounds of `fee_rate_governor` with the
+/// ones that were given.
+fn set_fee_bounds(
+    fee_rate_governor: &mut FeeRateGovernor,
+    min_lamports_per_signature: Option<u64>,
+    max_lamports_per_signature: Option<u64>,
+) -> io::Result<()> {
+    let min = min_lamports_per_signature.unwrap_or(fee_rate_governor.min_lamports_per_signature);
+    let max = max_lamports_per_signature.unwrap_or(fee_rate_governor.max_lamports_per_signature);
+    let target = fee_rate_governor.target_lamports_per_signature;
+    if !(min <= target && target <= max) {
+        return Err(io::Error::other(format!(
+            "error: lamports per signature bounds must satisfy min {min} <= target {target} <= \
+             max {max}"
+        )));
+    }
+    fee_rate_governor.min_lamports_per_signature = min;
+    fee_rate_governor.max_lamports_per_signature = max;
+    Ok(())
+}
+
+/// Overrides the rates of `inflation` with the ones that were given.
+fn custom_inflation(
+    mut inflation: Inflation,
+    initial: Option<f64>,
+    terminal: Option<f64>,
+    taper: Option<f64>,
+) -> io::Result<Inflation> {
+    for (name, rate) in [
+        ("initial", initial),
+        ("terminal", terminal),
+        ("taper", taper),
+    ] {
+        if let Some(rate) = rate.filter(|rate| !rate.is_finite() || *rate < 0.0) {
+            return Err(io::Error::other(format!(
+                "error: {name} inflation {rate} must be finite and non-negative"
+            )));
+        }
+    }
+    if let Some(initial) = initial {
+        inflation.initial = initial;
+    }
+    if let Some(terminal) = terminal {
+        inflation.terminal = terminal;
+    }
+    if let Some(taper) = taper {
+        inflation.taper = taper;
+    }
+    if inflation.initial < inflation.terminal {
+        return Err(io::Error::other(format!(
+            "error: initial inflation {} is lower than terminal inflation {}",
+            inflation.initial, inflation.terminal
+        )));
+    }
+    Ok(inflation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compute_faucet_lamports() {
+        assert_eq!(compute_faucet_lamports(None, None, 1_000).unwrap(), 0);
+        assert_eq!(compute_faucet_lamports(Some(42), None, 1_000).unwrap(), 42);
+        assert_eq!(compute_faucet_lamports(None, Some(25), 1_000).unwrap(), 250);
+        assert_eq!(
+            compute_faucet_lamports(None, Some(100), 1_000).unwrap(),
+            1_000
+        );
+        assert_eq!(compute_faucet_lamports(None, Some(0), 1_000).unwrap(), 0);
+        // Doesn't overflow on large allocations
+        assert_eq!(
+            compute_faucet_lamports(None, Some(50), u64::MAX).unwrap(),
+            u64::MAX / 2
+        );
+    }
+
+    #[test]
+    fn test_compute_faucet_lamports_both_given() {
+        assert!(compute_faucet_lamports(Some(42), Some(25), 1_000).is_err());
+    }
+
+    #[test]
+    fn test_set_fee_bounds() {
+        let mut fee_rate_governor = FeeRateGovernor::new(10_000, 20_000);
+        set_fee_bounds(&mut fee_rate_governor, None, None).unwrap();
+        assert_eq!(fee_rate_governor, FeeRateGovernor::new(10_000, 20_000));
+
+        set_fee_bounds(&mut fee_rate_governor, Some(1_000), Some(50_000)).unwrap();
+        assert_eq!(fee_rate_governor.min_lamports_per_signature, 1_000);
+        assert_eq!(fee_rate_governor.max_lamports_per_signature, 50_000);
+
+        // The bounds may be equal to the target
+        set_fee_bounds(&mut fee_rate_governor, Some(10_000), Some(10_000)).unwrap();
+        assert_eq!(fee_rate_governor.min_lamports_per_signature, 10_000);
+        assert_eq!(fee_rate_governor.max_lamports_per_signature, 10_000);
+    }
+
+    #[test]
+    fn test_set_fee_bounds_out_of_order() {
+        let mut fee_rate_governor = FeeRateGovernor::new(10_000, 20_000);
+        assert!(set_fee_bounds(&mut fee_rate_governor, Some(50_000), Some(1_000)).is_err());
+        // min above the target
+        assert!(set_fee_bounds(&mut fee_rate_governor, Some(20_000), None).is_err());
+        // max below the target
+        assert!(set_fee_bounds(&mut fee_rate_governor, None, Some(5_000)).is_err());
+        assert_eq!(fee_rate_governor, FeeRateGovernor::new(10_000, 20_000));
+    }
+
+    #[test]
+    fn test_custom_inflation() {
+        assert_eq!(
+            custom_inflation(Inflation::full(), None, None, None).unwrap(),
+            Inflation::full()
+        );
+
+        let inflation =
+            custom_inflation(Inflation::pico(), Some(0.1), Some(0.02), Some(0.2)).unwrap();
+        assert_eq!(inflation.initial, 0.1);
+        assert_eq!(inflation.terminal, 0.02);
+        assert_eq!(inflation.taper, 0.2);
+        assert_eq!(inflation.foundation, Inflation::pico().foundation);
+
+        // Only the given rates are overridden
+        let inflation = custom_inflation(Inflation::full(), None, Some(0.01), None).unwrap();
+        assert_eq!(inflation.initial, Inflation::full().initial);
+        assert_eq!(inflation.terminal, 0.01);
+        assert_eq!(inflation.taper, Inflation::full().taper);
+    }
+
+    #[test]
+    fn test_custom_inflation_initial_below_terminal() {
+        assert!(custom_inflation(Inflation::default(), Some(0.01), Some(0.02), None).is_err());
+        // The preset's initial rate is checked against the given terminal rate
+        assert!(custom_inflation(Inflation::new_disabled(), None, Some(0.01), None).is_err());
+    }
+
+    #[test]
+    fn test_custom_inflation_invalid_rates() {
+        for rate in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.01] {
+            assert!(custom_inflation(Inflation::full(), Some(rate), None, None).is_err());
+            assert!(custom_inflation(Inflation::full(), None, Some(rate), None).is_err());
+            assert!(custom_inflation(Inflation::full(), None, None, Some(rate)).is_err());
+        }
+        // Zero rates are allowed
+        assert!(custom_inflation(Inflation::full(), Some(0.0), Some(0.0), Some(0.0)).is_ok());
+    }
+
+    #[test]
+    fn test_format_genesis_config() {
+        let mut genesis_config = GenesisConfig {
+            ticks_per_slot: 42,
+            cluster_type: ClusterType::Development,
+            ..GenesisConfig::default()
+        };
+        let pubkey = Pubkey::new_unique();
+        genesis_config.add_account(
+            pubkey,
+            AccountSharedData::new(1_000, 0, &system_program::id()),
+        );
+
+        let json = format_genesis_config(&genesis_config, "json").unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["cluster_type"], "Development");
+        assert_eq!(value["ticks_per_slot"], 42);
+        assert_eq!(value["accounts"][pubkey.to_string()]["lamports"], 1_000);
+        assert_eq!(value["hash"], genesis_config.hash().to_string());
+        assert_eq!(value["total_lamports"], 1_000);
+
+        let yaml = format_genesis_config(&genesis_config, "yaml").unwrap();
+        assert!(yaml.contains("ticks_per_slot: 42"));
+
+        assert!(format_genesis_config(&genesis_config, "toml").is_err());
+    }
+
+    // A genesis config with two bootstrap validators, and the identity, vote
+    // and stake pubkeys of each one
+    fn two_validator_genesis(
+        authorized_withdrawer: Option<&Pubkey>,
+    ) -> ([Pubkey; 6], GenesisConfig) {
+        let rent = Rent::default();
+        let pubkeys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let mut genesis_config = GenesisConfig::default();
+        add_validator_accounts(
+            &mut genesis_config,
+            &mut pubkeys.iter(),
+            LAMPORTS_PER_SOL,
+            rent.minimum_balance(StakeStateV2::size_of()),
+            100,
+            &rent,
+            None,
+            authorized_withdrawer,
+        )
+        .unwrap();
+        (pubkeys, genesis_config)
+    }
+
+    #[test]
+    fn test_add_validator_accounts_authorized_withdrawer() {
+        let authorized_withdrawer = Pubkey::new_unique();
+        let authorized_withdrawer_of = |genesis_config: &GenesisConfig, vote_pubkey: &Pubkey| {
+            let vote_account = &genesis_config.accounts[vote_pubkey];
+            VoteStateV3::deserialize(&vote_account.data)
+                .unwrap()
+                .authorized_withdrawer
+        };
+
+        let (pubkeys, genesis_config) = two_validator_genesis(Some(&authorized_withdrawer));
+        assert_eq!(
+            authorized_withdrawer_of(&genesis_config, &pubkeys[1]),
+            authorized_withdrawer
+        );
+        assert_eq!(
+            authorized_withdrawer_of(&genesis_config, &pubkeys[4]),
+            authorized_withdrawer
+        );
+
+        // defaults to the validator identity
+        let (pubkeys, genesis_config) = two_validator_genesis(None);
+        assert_eq!(
+            authorized_withdrawer_of(&genesis_config, &pubkeys[1]),
+            pubkeys[0]
+        );
+    }
+
+    #[test]
+    fn test_check_bootstrap_delegations() {
+        let (pubkeys, mut genesis_config) = two_validator_genesis(None);
+        check_bootstrap_delegations(&genesis_config, &pubkeys).unwrap();
+
+        // the stake accounts delegate to the other validator's vote account
+        let swapped = [
+            pubkeys[0], pubkeys[1], pubkeys[5], pubkeys[3], pubkeys[4], pubkeys[2],
+        ];
+        assert!(check_bootstrap_delegations(&genesis_config, &swapped).is_err());
+
+        // the stake account is missing
+        genesis_config.accounts.remove(&pubkeys[5]);
+        assert!(check_bootstrap_delegations(&genesis_config, &pubkeys).is_err());
+    }
+
+    #[test]
+    fn test_verify_written_genesis_config() {
+        let ledger_path = TempDir::new().unwrap();
+        let mut fee_rate_governor = FeeRateGovernor::new(20_000, 40_000);
+        set_fee_bounds(&mut fee_rate_governor, Some(5_000), Some(200_000)).unwrap();
+        fee_rate_governor.burn_percent = 25;
+        let mut genesis_config = GenesisConfig {
+            fee_rate_governor,
+            rent: Rent {
+                lamports_per_byte_year: 42,
+                ..Rent::default()
+            },
+            epoch_schedule: EpochSchedule::custom(64, 64, false),
+            ..GenesisConfig::default()
+        };
+        genesis_config.write(ledger_path.path()).unwrap();
+        verify_written_genesis_config(ledger_path.path(), &genesis_config).unwrap();
+
+        genesis_config.fee_rate_governor.burn_percent = 50;
+        let err = verify_written_genesis_config(ledger_path.path(), &genesis_config).unwrap_err();
+        assert!(err.to_string().contains("fee rate governor"));
+    }
+
+    #[test]
+    fn test_total_lamports() {
+        let rent = Rent::default();
+        let stake_lamports = rent.minimum_balance(StakeStateV2::size_of());
+        let (_, mut genesis_config) = two_validator_genesis(None);
+        let vote_lamports = VoteStateV3::get_rent_exempt_reserve(&rent).max(1);
+        let validator_lamports = 2 * (LAMPORTS_PER_SOL + stake_lamports + vote_lamports);
+        assert_eq!(total_lamports(&genesis_config), validator_lamports);
+
+        let faucet_lamports = 500 * LAMPORTS_PER_SOL;
+        genesis_config.add_account(
+            Pubkey::new_unique(),
+            AccountSharedData::new(faucet_lamports, 0, &system_program::id()),
+        );
+        assert_eq!(
+            total_lamports(&genesis_config),
+            validator_lamports + faucet_lamports
+        );
+    }
+
+    #[test]
+    fn test_enable_warmup_epochs_development_default_on() {
+        assert!(enable_warmup_epochs(None, ClusterType::Development));
+        assert!(!enable_warmup_epochs(Some(false), ClusterType::Development));
+    }
+
+    #[test]
+    fn test_enable_warmup_epochs_mainnet_default_off() {
+        assert!(!enable_warmup_epochs(None, ClusterType::MainnetBeta));
+        assert!(!enable_warmup_epochs(None, ClusterType::Testnet));
+        assert!(enable_warmup_epochs(Some(true), ClusterType::MainnetBeta));
+    }
+}
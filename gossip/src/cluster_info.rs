@@ -1,5 +1,5 @@
-use blockchain_net_utils::PortRange;
-use std::net::IpAddr;
+use blockchain_net_utils::{classify_addr, AddrClass, PortRange};
+use std::net::{IpAddr, TcpListener, UdpSocket};
 use std::ops::Deref;
 
 #[derive(Debug, Clone)]
@@ -19,7 +19,10 @@ impl BindIpAddrs {
         }
         if addrs.len() > 1 {
             for &ip in &addrs {
-                if ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() {
+                if matches!(
+                    classify_addr(ip),
+                    AddrClass::Loopback | AddrClass::Unspecified | AddrClass::Multicast
+                ) {
                     return Err(format!(
                         "Invalid configuration: {ip:?} is not allowed with multiple \
                          --bind-address values (loopback, unspecified, or multicast)"
@@ -35,6 +38,13 @@ impl BindIpAddrs {
     pub fn primary(&self) -> IpAddr {
         self.addrs[0]
     }
+
+    /// Skips the validation of [`BindIpAddrs::new`], so that tests can use
+    /// multiple loopback addresses.
+    #[cfg(test)]
+    pub(crate) fn new_unchecked(addrs: Vec<IpAddr>) -> Self {
+        Self { addrs }
+    }
 }
 
 // Makes BindIpAddrs behave like &[IpAddr]
@@ -61,7 +71,14 @@ pub struct NodeConfig {
     pub port_range: PortRange,
     /// Multihoming: The IP addresses the node can bind to
     pub bind_ip_addrs: BindIpAddrs,
+    /// The number of gossip receive sockets sharing the gossip port through
+    /// SO_REUSEPORT. Clamped to 1 on platforms without it
+    pub num_gossip_sockets: usize,
 }
 
 #[derive(Debug)]
-pub struct Sockets {}
+pub struct Sockets {
+    /// The gossip receive sockets, all bound to the gossip port. Never empty
+    pub gossip: Vec<UdpSocket>,
+    pub ip_echo: Option<TcpListener>,
+}
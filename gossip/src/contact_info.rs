@@ -1,21 +1,303 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use solana_pubkey::Pubkey;
 use solana_serde_varint as serde_varint;
+use std::cmp::Ordering;
+use std::net::SocketAddr;
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(from = "VersionedContactInfo", into = "VersionedContactInfo")]
 pub struct ContactInfo {
+    pubkey: Pubkey,
+    wallclock: u64,
+    // Identifies the cluster fork the node is on, see compute_shred_version.
+    shred_version: u16,
+    // The gossip address advertised to the cluster.
+    gossip: Option<SocketAddr>,
+}
+
+/// The contact info layout from before the shred version and the gossip
+/// address were advertised.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct LegacyContactInfo {
     pubkey: Pubkey,
     #[serde(with = "serde_varint")]
     wallclock: u64,
 }
 
+/// Wire encoding of [`ContactInfo`]. Layout changes go into a new variant, so
+/// that the older ones still deserialize.
+#[derive(Deserialize, Serialize)]
+enum VersionedContactInfo {
+    Legacy(LegacyContactInfo),
+    V1 {
+        pubkey: Pubkey,
+        #[serde(with = "serde_varint")]
+        wallclock: u64,
+        shred_version: u16,
+        gossip: Option<SocketAddr>,
+    },
+}
+
 impl ContactInfo {
     pub fn new(pubkey: Pubkey, wallclock: u64) -> Self {
-        Self { pubkey, wallclock }
+        Self::new_with_shred_version(pubkey, wallclock, 0)
+    }
+
+    pub fn new_with_shred_version(pubkey: Pubkey, wallclock: u64, shred_version: u16) -> Self {
+        Self {
+            pubkey,
+            wallclock,
+            shred_version,
+            gossip: None,
+        }
     }
 
     #[inline]
     pub fn pubkey(&self) -> &Pubkey {
         &self.pubkey
     }
+
+    #[inline]
+    pub fn wallclock(&self) -> u64 {
+        self.wallclock
+    }
+
+    /// Returns whether the contact info is more than `ttl_ms` older than `now`,
+    /// both in milliseconds since the UNIX epoch.
+    pub fn is_expired(&self, now: u64, ttl_ms: u64) -> bool {
+        now.saturating_sub(self.wallclock) > ttl_ms
+    }
+
+    #[inline]
+    pub fn shred_version(&self) -> u16 {
+        self.shred_version
+    }
+
+    pub fn set_shred_version(&mut self, shred_version: u16) {
+        self.shred_version = shred_version
+    }
+
+    #[inline]
+    pub fn gossip(&self) -> Option<SocketAddr> {
+        self.gossip
+    }
+
+    pub fn set_gossip(&mut self, gossip: SocketAddr) {
+        self.gossip = Some(gossip)
+    }
+
+    /// Returns whether a node with `my_shred_version` can talk to this one.
+    /// A shred version of 0 on either side is unknown and matches any version.
+    pub fn is_compatible(&self, my_shred_version: u16) -> bool {
+        self.shred_version == 0 || my_shred_version == 0 || self.shred_version == my_shred_version
+    }
+}
+
+impl From<LegacyContactInfo> for ContactInfo {
+    fn from(contact_info: LegacyContactInfo) -> Self {
+        let LegacyContactInfo { pubkey, wallclock } = contact_info;
+        Self::new(pubkey, wallclock)
+    }
+}
+
+impl From<VersionedContactInfo> for ContactInfo {
+    fn from(contact_info: VersionedContactInfo) -> Self {
+        match contact_info {
+            VersionedContactInfo::Legacy(contact_info) => Self::from(contact_info),
+            VersionedContactInfo::V1 {
+                pubkey,
+                wallclock,
+                shred_version,
+                gossip,
+            } => Self {
+                pubkey,
+                wallclock,
+                shred_version,
+                gossip,
+            },
+        }
+    }
+}
+
+impl From<ContactInfo> for VersionedContactInfo {
+    fn from(contact_info: ContactInfo) -> Self {
+        let ContactInfo {
+            pubkey,
+            wallclock,
+            shred_version,
+            gossip,
+        } = contact_info;
+        Self::V1 {
+            pubkey,
+            wallclock,
+            shred_version,
+            gossip,
+        }
+    }
+}
+
+/// Orders by pubkey, then by wallclock, so that the entries of a node sort
+/// oldest first. The remaining fields only break ties, to stay consistent with
+/// `Eq`.
+impl Ord for ContactInfo {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.pubkey
+            .cmp(&other.pubkey)
+            .then(self.wallclock.cmp(&other.wallclock))
+            .then(self.shred_version.cmp(&other.shred_version))
+            .then(self.gossip.cmp(&other.gossip))
+    }
+}
+
+impl PartialOrd for ContactInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Keeps only the newest contact info of each node, by wallclock. Entries with
+/// the same wallclock are told apart by the rest of the [`Ord`] fields, the
+/// greatest one being kept, so the result doesn't depend on the input order.
+/// The result is sorted by pubkey.
+pub fn dedup_contact_infos(mut contact_infos: Vec<ContactInfo>) -> Vec<ContactInfo> {
+    // Greatest first within each pubkey, so that dedup keeps it
+    contact_infos.sort_unstable_by(|a, b| a.pubkey.cmp(&b.pubkey).then_with(|| b.cmp(a)));
+    contact_infos.dedup_by_key(|contact_info| contact_info.pubkey);
+    contact_infos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_compatible() {
+        let contact_info = ContactInfo::new_with_shred_version(Pubkey::new_unique(), 0, 42);
+        assert_eq!(contact_info.shred_version(), 42);
+        assert!(contact_info.is_compatible(42));
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let contact_info = ContactInfo::new(Pubkey::new_unique(), 1_000);
+        assert!(!contact_info.is_expired(1_500, 500));
+        assert!(contact_info.is_expired(1_501, 500));
+        // wallclock ahead of now
+        assert!(!contact_info.is_expired(900, 0));
+    }
+
+    #[test]
+    fn test_is_incompatible() {
+        let contact_info = ContactInfo::new_with_shred_version(Pubkey::new_unique(), 0, 42);
+        assert!(!contact_info.is_compatible(43));
+    }
+
+    #[test]
+    fn test_is_compatible_wildcard() {
+        let mut contact_info = ContactInfo::new_with_shred_version(Pubkey::new_unique(), 0, 42);
+        assert!(contact_info.is_compatible(0));
+        contact_info.set_shred_version(0);
+        assert!(contact_info.is_compatible(43));
+    }
+
+    #[test]
+    fn test_ord() {
+        let pubkey = Pubkey::new_unique();
+        let older = ContactInfo::new_with_shred_version(pubkey, 1, 42);
+        let newer = ContactInfo::new(pubkey, 2);
+        assert!(older < newer);
+
+        let (low, high) = {
+            let a = Pubkey::new_unique();
+            let b = Pubkey::new_unique();
+            (a.min(b), a.max(b))
+        };
+        // The pubkey takes precedence over the wallclock
+        assert!(ContactInfo::new(low, 10) < ContactInfo::new(high, 1));
+        assert_eq!(
+            ContactInfo::new(low, 1).cmp(&ContactInfo::new(low, 1)),
+            Ordering::Equal
+        );
+        assert_ne!(
+            ContactInfo::new(low, 1).cmp(&ContactInfo::new_with_shred_version(low, 1, 1)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_deserialize_legacy() {
+        let pubkey = Pubkey::new_unique();
+        let legacy = LegacyContactInfo {
+            pubkey,
+            wallclock: 1_234,
+        };
+        // Variant index of VersionedContactInfo::Legacy, then the legacy layout
+        let mut bytes = 0u32.to_le_bytes().to_vec();
+        bytes.extend(bincode::serialize(&legacy).unwrap());
+
+        let contact_info: ContactInfo = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(contact_info, ContactInfo::new(pubkey, 1_234));
+        assert_eq!(contact_info.shred_version(), 0);
+        assert_eq!(contact_info.gossip(), None);
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let mut contact_info = ContactInfo::new_with_shred_version(Pubkey::new_unique(), 7, 42);
+        contact_info.set_gossip(SocketAddr::from(([127, 0, 0, 1], 8001)));
+        let bytes = bincode::serialize(&contact_info).unwrap();
+        assert_eq!(
+            bincode::deserialize::<ContactInfo>(&bytes).unwrap(),
+            contact_info
+        );
+    }
+
+    #[test]
+    fn test_dedup_contact_infos() {
+        let pubkey = Pubkey::new_unique();
+        let other_pubkey = Pubkey::new_unique();
+        let contact_infos = vec![
+            ContactInfo::new(pubkey, 2),
+            ContactInfo::new(other_pubkey, 7),
+            ContactInfo::new(pubkey, 5),
+            ContactInfo::new(pubkey, 3),
+            ContactInfo::new(other_pubkey, 7),
+        ];
+
+        let mut expected = vec![
+            ContactInfo::new(pubkey, 5),
+            ContactInfo::new(other_pubkey, 7),
+        ];
+        expected.sort();
+        assert_eq!(dedup_contact_infos(contact_infos), expected);
+        assert_eq!(dedup_contact_infos(vec![]), vec![]);
+    }
+
+    #[test]
+    fn test_dedup_contact_infos_same_wallclock() {
+        let pubkey = Pubkey::new_unique();
+        let mut with_gossip = ContactInfo::new_with_shred_version(pubkey, 5, 2);
+        with_gossip.set_gossip(SocketAddr::from(([127, 0, 0, 1], 8001)));
+        let contact_infos = vec![
+            ContactInfo::new_with_shred_version(pubkey, 5, 1),
+            ContactInfo::new_with_shred_version(pubkey, 5, 2),
+            with_gossip.clone(),
+            ContactInfo::new_with_shred_version(pubkey, 5, 0),
+        ];
+
+        // The same entry wins whatever the input order
+        for shift in 0..contact_infos.len() {
+            let mut contact_infos = contact_infos.clone();
+            contact_infos.rotate_left(shift);
+            assert_eq!(
+                dedup_contact_infos(contact_infos.clone()),
+                vec![with_gossip.clone()]
+            );
+            contact_infos.reverse();
+            assert_eq!(
+                dedup_contact_infos(contact_infos),
+                vec![with_gossip.clone()]
+            );
+        }
+    }
 }
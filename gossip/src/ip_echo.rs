@@ -0,0 +1,159 @@
+//! A blocking ip echo server, which tells the peers connecting to it the IP
+//! address they are seen from.
+//!
+//! It speaks the wire format of the net-utils ip echo server, so the net-utils
+//! clients can query it, but it doesn't probe the ports listed in requests.
+
+use log::{debug, info, warn};
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait before polling the listener again when no connection is
+/// pending.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Applies to reading the request and writing the response.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Connections beyond this many concurrent sessions are dropped right away, so
+/// that slow peers can't pile up threads.
+const MAX_SESSIONS: usize = 64;
+
+/// Null bytes starting requests and responses, so they can't be confused with
+/// HTTP.
+const HEADER_LENGTH: usize = 4;
+/// The header, the TCP and UDP ports to probe (4 u16s each) and a trailing
+/// newline.
+const REQUEST_LENGTH: usize = HEADER_LENGTH + 16 + 1;
+/// The header and the serialized address and shred version, with room for an
+/// IPv6 address.
+const RESPONSE_LENGTH: usize = HEADER_LENGTH + 23;
+
+/// Serves ip echo requests on `listener` until `exit` is set, replying to each
+/// peer with the IP address it connected from. Each connection is served on
+/// its own thread, up to `MAX_SESSIONS` at a time.
+pub fn run(listener: TcpListener, exit: Arc<AtomicBool>) -> io::Result<()> {
+    listener.set_nonblocking(true)?;
+    info!("ip echo server listening on {:?}", listener.local_addr()?);
+    let sessions = Arc::new(AtomicUsize::new(0));
+    while !exit.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, peer_addr)) => {
+                if sessions.fetch_add(1, Ordering::AcqRel) >= MAX_SESSIONS {
+                    sessions.fetch_sub(1, Ordering::AcqRel);
+                    debug!("ip echo dropping connection from {peer_addr}: too many sessions");
+                    continue;
+                }
+                let session = {
+                    let sessions = sessions.clone();
+                    thread::Builder::new()
+                        .name("solIpEchoSrvr".to_string())
+                        .spawn(move || {
+                            if let Err(err) = process_connection(stream, peer_addr) {
+                                info!("ip echo session with {peer_addr} failed: {err}");
+                            }
+                            sessions.fetch_sub(1, Ordering::AcqRel);
+                        })
+                };
+                if let Err(err) = session {
+                    sessions.fetch_sub(1, Ordering::AcqRel);
+                    warn!("ip echo failed to spawn a session thread: {err}");
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(err) => warn!("ip echo accept failed: {err}"),
+        }
+    }
+    Ok(())
+}
+
+fn process_connection(mut stream: TcpStream, peer_addr: SocketAddr) -> io::Result<()> {
+    debug!("ip echo connection from {peer_addr}");
+    // Accepted sockets inherit the non-blocking mode of the listener on some
+    // platforms
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut request = [0u8; REQUEST_LENGTH];
+    stream.read_exact(&mut request)?;
+    if request[..HEADER_LENGTH] != [0u8; HEADER_LENGTH] {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("bad request header {:?}", &request[..HEADER_LENGTH]),
+        ));
+    }
+
+    stream.write_all(&response(peer_addr.ip())?)
+}
+
+fn response(peer_ip: IpAddr) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0u8; RESPONSE_LENGTH];
+    // No shred version is advertised
+    bincode::serialize_into(&mut bytes[HEADER_LENGTH..], &(peer_ip, None::<u16>))
+        .map_err(io::Error::other)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockchain_net_utils::get_public_ip_addr_with_binding;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_run() {
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let listener = TcpListener::bind(SocketAddr::new(localhost, 0)).unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let exit = Arc::new(AtomicBool::new(false));
+        let server = {
+            let exit = exit.clone();
+            thread::spawn(move || run(listener, exit))
+        };
+
+        assert_eq!(
+            get_public_ip_addr_with_binding(&server_addr, localhost).unwrap(),
+            localhost
+        );
+
+        // A bad request doesn't stop the server
+        let mut stream = TcpStream::connect(server_addr).unwrap();
+        stream.write_all(&[1u8; REQUEST_LENGTH]).unwrap();
+        assert_eq!(
+            get_public_ip_addr_with_binding(&server_addr, localhost).unwrap(),
+            localhost
+        );
+
+        exit.store(true, Ordering::Relaxed);
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_run_concurrent_sessions() {
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let listener = TcpListener::bind(SocketAddr::new(localhost, 0)).unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let exit = Arc::new(AtomicBool::new(false));
+        let server = {
+            let exit = exit.clone();
+            thread::spawn(move || run(listener, exit))
+        };
+
+        // A peer that never sends its request doesn't hold up the others
+        let _idle = TcpStream::connect(server_addr).unwrap();
+        let start = std::time::Instant::now();
+        assert_eq!(
+            get_public_ip_addr_with_binding(&server_addr, localhost).unwrap(),
+            localhost
+        );
+        assert!(start.elapsed() < IO_TIMEOUT);
+
+        exit.store(true, Ordering::Relaxed);
+        server.join().unwrap().unwrap();
+    }
+}
@@ -1,3 +1,6 @@
 pub mod cluster_info;
-mod contact_info;
+pub mod contact_info;
+pub mod ip_echo;
 pub mod node;
+pub mod peer_table;
+pub mod protocol;
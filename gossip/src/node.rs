@@ -1,15 +1,23 @@
-use crate::cluster_info::{NodeConfig, Sockets};
+use crate::cluster_info::{BindIpAddrs, NodeConfig, Sockets};
 use crate::contact_info::ContactInfo;
-use blockchain_net_utils::sockets::bind_gossip_port_in_range;
-use log::{info, trace};
+use blockchain_net_utils::sockets::{
+    bind_gossip_port_in_range, bind_more_with_config, SocketConfiguration,
+};
+use blockchain_net_utils::PortRange;
+use log::{info, trace, warn};
 use solana_pubkey::Pubkey;
 use solana_time_utils::timestamp;
+use std::io;
 use std::net::SocketAddr;
 
 #[derive(Debug)]
 pub struct Node {
     pub info: ContactInfo,
     pub sockets: Sockets,
+    /// Multihoming: the IP addresses the node can bind to
+    pub bind_ip_addrs: BindIpAddrs,
+    /// The port range the gossip port was bound in
+    port_range: PortRange,
 }
 
 impl Node {
@@ -19,23 +27,152 @@ impl Node {
             gossip_port,
             port_range,
             bind_ip_addrs,
+            num_gossip_sockets,
         } = config;
 
         let bind_ip_addr = bind_ip_addrs.primary();
 
         let gossip_addr = SocketAddr::new(advertised_ip, gossip_port);
-        //let (gossip_port, (gossip, ip_echo)) =
-        bind_gossip_port_in_range(&gossip_addr, port_range, bind_ip_addr);
+        let (gossip_port, (gossip, ip_echo)) =
+            bind_gossip_port_in_range(&gossip_addr, port_range, bind_ip_addr);
+        let gossip = match gossip.try_clone().and_then(|socket| {
+            bind_more_with_config(
+                socket,
+                num_gossip_sockets.max(1),
+                SocketConfiguration::default(),
+            )
+        }) {
+            Ok(gossip) => gossip,
+            Err(err) => {
+                warn!("Failed to bind {num_gossip_sockets} gossip sockets, using one: {err}");
+                vec![gossip]
+            }
+        };
 
-        let info = ContactInfo::new(
+        let mut info = ContactInfo::new(
             *pubkey,
             timestamp(), // wallclock
         );
+        info.set_gossip(SocketAddr::new(advertised_ip, gossip_port));
 
         trace!("new ContactInfo: {info:?}");
-        let sockets = Sockets {};
+        let sockets = Sockets {
+            gossip,
+            ip_echo: Some(ip_echo),
+        };
 
         info!("Bound all network sockets as follows: {:#?}", &sockets);
-        Node { info, sockets }
+        Node {
+            info,
+            sockets,
+            bind_ip_addrs,
+            port_range,
+        }
+    }
+
+    /// Multihoming failover: rebinds the gossip sockets to the secondary bind
+    /// address at `index` on the same port, and advertises that address in the
+    /// node's [`ContactInfo`]. Index 0 is the primary address and is rejected.
+    pub fn rebind_to_secondary(&mut self, index: usize) -> io::Result<()> {
+        if index == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "bind address index 0 is the primary address, not a secondary one",
+            ));
+        }
+        let bind_ip_addr = *self.bind_ip_addrs.get(index).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "bind address index {index} out of range, {} address(es) configured",
+                    self.bind_ip_addrs.len()
+                ),
+            )
+        })?;
+        let gossip_addr =
+            SocketAddr::new(bind_ip_addr, self.sockets.gossip[0].local_addr()?.port());
+        let (gossip_port, (gossip, ip_echo)) =
+            bind_gossip_port_in_range(&gossip_addr, self.port_range, bind_ip_addr);
+        let gossip = bind_more_with_config(
+            gossip,
+            self.sockets.gossip.len(),
+            SocketConfiguration::default(),
+        )?;
+
+        let gossip_addr = SocketAddr::new(bind_ip_addr, gossip_port);
+        info!("Rebound gossip socket to {gossip_addr}");
+        self.sockets = Sockets {
+            gossip,
+            ip_echo: Some(ip_echo),
+        };
+        self.info.set_gossip(gossip_addr);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn new_node(bind_ip_addrs: Vec<IpAddr>, num_gossip_sockets: usize) -> Node {
+        let config = NodeConfig {
+            advertised_ip: bind_ip_addrs[0],
+            gossip_port: 0,
+            port_range: (42_000, 43_000),
+            bind_ip_addrs: BindIpAddrs::new_unchecked(bind_ip_addrs),
+            num_gossip_sockets,
+        };
+        Node::new_with_external_ip(&Pubkey::new_unique(), config)
+    }
+
+    #[test]
+    fn test_new_with_multiple_gossip_sockets() {
+        let node = new_node(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)], 4);
+        let expected = if cfg!(any(windows, target_os = "ios")) {
+            1
+        } else {
+            4
+        };
+        assert_eq!(node.sockets.gossip.len(), expected);
+        let gossip_addr = node.info.gossip().unwrap();
+        for socket in &node.sockets.gossip {
+            assert_eq!(socket.local_addr().unwrap(), gossip_addr);
+        }
+
+        // No sockets at all is not an option
+        let node = new_node(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)], 0);
+        assert_eq!(node.sockets.gossip.len(), 1);
+    }
+
+    #[test]
+    fn test_rebind_to_secondary() {
+        let primary = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+        let secondary = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2));
+        let mut node = new_node(vec![primary, secondary], 2);
+        let gossip_addr = node.sockets.gossip[0].local_addr().unwrap();
+        assert_eq!(gossip_addr.ip(), primary);
+        assert_eq!(node.info.gossip(), Some(gossip_addr));
+
+        node.rebind_to_secondary(1).unwrap();
+        let secondary_addr = SocketAddr::new(secondary, gossip_addr.port());
+        assert_eq!(node.sockets.gossip.len(), 2);
+        for socket in &node.sockets.gossip {
+            assert_eq!(socket.local_addr().unwrap(), secondary_addr);
+        }
+        assert_eq!(node.info.gossip(), Some(secondary_addr));
+    }
+
+    #[test]
+    fn test_rebind_to_secondary_out_of_range() {
+        let mut node = new_node(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)], 1);
+        let gossip_addr = node.info.gossip();
+
+        // Index 0 is the primary address, not a secondary one
+        for index in [0, 1] {
+            let err = node.rebind_to_secondary(index).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(node.info.gossip(), gossip_addr);
+        }
     }
 }
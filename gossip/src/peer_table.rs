@@ -0,0 +1,104 @@
+use crate::contact_info::ContactInfo;
+use solana_pubkey::Pubkey;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+struct Peer {
+    contact_info: ContactInfo,
+    /// Local time when the contact info was inserted, in milliseconds
+    insert_timestamp: u64,
+}
+
+/// The latest known [`ContactInfo`] of each peer, keyed by pubkey.
+#[derive(Debug, Default)]
+pub struct PeerTable {
+    peers: HashMap<Pubkey, Peer>,
+}
+
+impl PeerTable {
+    /// Inserts `contact_info` unless a contact info with the same or a newer
+    /// wallclock is already known for the pubkey. Returns whether it was
+    /// inserted.
+    pub fn insert(&mut self, contact_info: ContactInfo, now: u64) -> bool {
+        if let Some(peer) = self.peers.get(contact_info.pubkey()) {
+            if peer.contact_info.wallclock() >= contact_info.wallclock() {
+                return false;
+            }
+        }
+        self.peers.insert(
+            *contact_info.pubkey(),
+            Peer {
+                contact_info,
+                insert_timestamp: now,
+            },
+        );
+        true
+    }
+
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&ContactInfo> {
+        self.peers.get(pubkey).map(|peer| &peer.contact_info)
+    }
+
+    /// Returns when the current contact info of the peer was inserted.
+    pub fn insert_timestamp(&self, pubkey: &Pubkey) -> Option<u64> {
+        self.peers.get(pubkey).map(|peer| peer.insert_timestamp)
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Drops the peers whose contact info is older than `ttl_ms`, returning
+    /// how many were dropped.
+    pub fn purge_expired(&mut self, now: u64, ttl_ms: u64) -> usize {
+        let num_peers = self.peers.len();
+        self.peers
+            .retain(|_, peer| !peer.contact_info.is_expired(now, ttl_ms));
+        num_peers - self.peers.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_newer_wins() {
+        let pubkey = Pubkey::new_unique();
+        let mut table = PeerTable::default();
+        assert!(table.is_empty());
+
+        assert!(table.insert(ContactInfo::new_with_shred_version(pubkey, 100, 1), 1_000));
+        // older and same wallclock are ignored
+        assert!(!table.insert(ContactInfo::new_with_shred_version(pubkey, 99, 2), 1_001));
+        assert!(!table.insert(ContactInfo::new_with_shred_version(pubkey, 100, 2), 1_002));
+        assert_eq!(table.get(&pubkey).unwrap().shred_version(), 1);
+        assert_eq!(table.insert_timestamp(&pubkey), Some(1_000));
+
+        assert!(table.insert(ContactInfo::new_with_shred_version(pubkey, 101, 3), 1_003));
+        assert_eq!(table.get(&pubkey).unwrap().shred_version(), 3);
+        assert_eq!(table.insert_timestamp(&pubkey), Some(1_003));
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let stale = Pubkey::new_unique();
+        let fresh = Pubkey::new_unique();
+        let mut table = PeerTable::default();
+        table.insert(ContactInfo::new(stale, 1_000), 1_000);
+        table.insert(ContactInfo::new(fresh, 1_900), 1_900);
+
+        assert_eq!(table.purge_expired(2_000, 500), 1);
+        assert!(table.get(&stale).is_none());
+        assert!(table.get(&fresh).is_some());
+
+        assert_eq!(table.purge_expired(2_000, 500), 0);
+        assert_eq!(table.purge_expired(2_401, 500), 1);
+        assert!(table.is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::contact_info::ContactInfo;
+use serde::{Deserialize, Serialize};
+use solana_pubkey::Pubkey;
+
+/// Gossip protocol messages, exchanged over the gossip socket.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Protocol {
+    /// Asks a peer for the contact infos it knows about, carrying the
+    /// requester's own contact info so the peer can respond.
+    PullRequest(ContactInfo),
+    /// Response to a pull request from the node with the given pubkey.
+    PullResponse(Pubkey, Vec<ContactInfo>),
+    /// Contact infos pushed by the node with the given pubkey.
+    PushMessage(Pubkey, Vec<ContactInfo>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    fn new_contact_info() -> ContactInfo {
+        let mut contact_info = ContactInfo::new_with_shred_version(Pubkey::new_unique(), 1_234, 42);
+        contact_info.set_gossip(SocketAddr::from((Ipv4Addr::LOCALHOST, 8001)));
+        contact_info
+    }
+
+    fn round_trip(message: Protocol) {
+        let bytes = bincode::serialize(&message).unwrap();
+        assert_eq!(bincode::deserialize::<Protocol>(&bytes).unwrap(), message);
+    }
+
+    #[test]
+    fn test_pull_request_round_trip() {
+        round_trip(Protocol::PullRequest(new_contact_info()));
+    }
+
+    #[test]
+    fn test_pull_response_round_trip() {
+        round_trip(Protocol::PullResponse(
+            Pubkey::new_unique(),
+            vec![new_contact_info(), new_contact_info()],
+        ));
+        round_trip(Protocol::PullResponse(Pubkey::new_unique(), vec![]));
+    }
+
+    #[test]
+    fn test_push_message_round_trip() {
+        round_trip(Protocol::PushMessage(
+            Pubkey::new_unique(),
+            vec![
+                new_contact_info(),
+                ContactInfo::new(Pubkey::new_unique(), 0),
+            ],
+        ));
+    }
+}
@@ -1,26 +1,35 @@
-use crate::blockstore::column::Column;
+use crate::blockstore::column::{Column, TypedColumn};
 use crate::blockstore_db::{IteratorMode, LedgerColumn, Rocks};
-use crate::blockstore_meta::TransactionStatusIndexMeta;
+use crate::blockstore_meta::{
+    AddressSignatureMeta, FrozenHashStatus, FrozenHashVersioned, Index,
+    OptimisticSlotMetaVersioned, PerfSample, SlotMeta, TransactionStatusIndexMeta,
+    TransactionStatusMeta,
+};
 use crate::blockstore_metrics::BlockstoreRpcApiMetrics;
 use crate::blockstore_options::{
-    BlockstoreOptions, LedgerColumnOptions, BLOCKSTORE_DIRECTORY_ROCKS_LEVEL,
+    AccessType, BlockstoreOptions, LedgerColumnOptions, BLOCKSTORE_DIRECTORY_ROCKS_LEVEL,
 };
+use crate::shred::{is_valid_data_shred_index, ShredFlags};
 use crate::slot_stats::SlotsStats;
-use bincode::deserialize;
-use blockchain_entry::entry::create_ticks;
+use blockchain_accounts_db::hardened_unpack::open_genesis_config;
+use blockchain_entry::entry::{create_ticks, next_hash, Entry};
 use blockchain_measure::measure::Measure;
+use blockchain_transaction_status_client_types::Rewards;
 use column::columns as cf;
-use crossbeam_channel::{Receiver, Sender};
-use log::info;
-use solana_clock::Slot;
+use crossbeam_channel::{Receiver, Sender, TrySendError};
+use log::{info, trace};
+use solana_clock::{Slot, UnixTimestamp};
 use solana_genesis_config::GenesisConfig;
 use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_signature::{Signature, SIGNATURE_BYTES};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 pub use {
     crate::blockstore::error::{BlockstoreError, Result},
+    crate::blockstore_db::IteratorDirection,
     rocksdb::properties as RocksProperties,
 };
 
@@ -36,6 +45,8 @@ pub fn create_new_ledger(
     max_genesis_archive_unpacked_size: u64,
     column_options: LedgerColumnOptions,
 ) -> Result<Hash> {
+    let ticks = genesis_ticks(genesis_config)?;
+    let last_hash = ticks.last().unwrap().hash;
     Blockstore::destroy(ledger_path)?;
     genesis_config.write(ledger_path)?;
 
@@ -49,19 +60,150 @@ pub fn create_new_ledger(
             ..BlockstoreOptions::default()
         },
     )?;
-    let ticks_per_slot = genesis_config.ticks_per_slot;
-    let hashes_per_tick = genesis_config.poh_config.hashes_per_tick.unwrap_or(0);
-    let entries = create_ticks(ticks_per_slot, hashes_per_tick, genesis_config.hash());
-    let last_hash = entries.last().unwrap().hash;
 
     //let shredder = Shredder::new(0, 0, 0, version).unwrap();
+    blockstore.insert_genesis_ticks(&ticks)?;
+    blockstore.set_roots(std::iter::once(&0))?;
 
     Ok(last_hash)
 }
 
+// Computes the hash `create_new_ledger` would return for `genesis_config`,
+// without touching disk. Takes the same arguments as `create_new_ledger`, so
+// tooling can validate its inputs before committing to a real run.
+pub fn create_new_ledger_dry_run(
+    _ledger_path: &Path,
+    genesis_config: &GenesisConfig,
+    _max_genesis_archive_unpacked_size: u64,
+    _column_options: LedgerColumnOptions,
+) -> Result<Hash> {
+    Ok(genesis_ticks(genesis_config)?.last().unwrap().hash)
+}
+
+// The ticks filling slot 0, which link back to the genesis config.
+//
+// Fails if slot 0 would have no ticks, or if `hashes_per_tick` is 1: PoH
+// needs at least one hash besides the tick itself.
+fn genesis_ticks(genesis_config: &GenesisConfig) -> Result<Vec<Entry>> {
+    let ticks_per_slot = genesis_config.ticks_per_slot;
+    if ticks_per_slot == 0 {
+        return Err(BlockstoreError::InvalidTicksPerSlot(ticks_per_slot));
+    }
+    let hashes_per_tick = genesis_config.poh_config.hashes_per_tick.unwrap_or(0);
+    if hashes_per_tick == 1 {
+        return Err(BlockstoreError::InvalidHashesPerTick(hashes_per_tick));
+    }
+    Ok(create_ticks(
+        ticks_per_slot,
+        hashes_per_tick,
+        genesis_config.hash(),
+    ))
+}
+
+// Sanity-checks the genesis of the ledger at `ledger_path` without starting a
+// validator: the genesis config must load, and slot 0 must be rooted and full
+// of ticks that chain from the genesis hash.
+//
+// Returns the genesis hash.
+pub fn verify_genesis_slot(
+    ledger_path: &Path,
+    max_genesis_archive_unpacked_size: u64,
+) -> Result<Hash> {
+    let genesis_config = open_genesis_config(ledger_path, max_genesis_archive_unpacked_size)?;
+    let genesis_hash = genesis_config.hash();
+    let blockstore = Blockstore::open_secondary(ledger_path)?;
+    if blockstore.roots_cf.get(0)? != Some(true) {
+        return Err(BlockstoreError::SlotNotRooted);
+    }
+    let slot_meta = blockstore
+        .meta_cf
+        .get(0)?
+        .ok_or(BlockstoreError::SlotUnavailable)?;
+    if !slot_meta.is_full() {
+        return Err(BlockstoreError::SlotNotFull(0));
+    }
+    let ticks = blockstore.get_genesis_ticks()?;
+    if !ticks_chain_from(&genesis_hash, &ticks) {
+        return Err(BlockstoreError::InvalidGenesisTicks(genesis_hash));
+    }
+    Ok(genesis_hash)
+}
+
+// Whether `ticks` are ticks only, each one the PoH continuation of the
+// previous one, starting from `start_hash`.
+fn ticks_chain_from(start_hash: &Hash, ticks: &[Entry]) -> bool {
+    let mut hash = *start_hash;
+    ticks.iter().all(|tick| {
+        let chains =
+            tick.transactions.is_empty() && next_hash(&hash, tick.num_hashes, &[]) == tick.hash;
+        hash = tick.hash;
+        chains
+    })
+}
+
+/// Updates `slot_meta` from the flags of the data shred at `index`: a data
+/// complete shred is recorded in `completed_data_indexes` and the last shred in
+/// the slot sets `last_index`. Shreds may arrive out of order, so `last_index`
+/// is only set by the first last shred received; a conflicting one is left to
+/// duplicate slot detection.
+///
+/// A shred index beyond [`MAX_DATA_SHREDS_PER_SLOT`] is rejected and leaves
+/// `slot_meta` untouched.
+///
+/// [`MAX_DATA_SHREDS_PER_SLOT`]: crate::shred::MAX_DATA_SHREDS_PER_SLOT
+pub fn process_data_shred_flags(
+    index: u32,
+    flags: ShredFlags,
+    slot_meta: &mut SlotMeta,
+) -> Result<()> {
+    if !is_valid_data_shred_index(u64::from(index)) {
+        return Err(BlockstoreError::InvalidDataShredIndex(
+            slot_meta.slot,
+            u64::from(index),
+        ));
+    }
+    if flags.contains(ShredFlags::DATA_COMPLETE_SHRED) {
+        slot_meta.completed_data_indexes.insert(index);
+    }
+    if flags.contains(ShredFlags::LAST_SHRED_IN_SLOT) && slot_meta.last_index.is_none() {
+        slot_meta.last_index = Some(u64::from(index));
+    }
+    Ok(())
+}
+
 pub type CompletedSlotsSender = Sender<Vec<Slot>>;
 pub type CompletedSlotsReceiver = Receiver<Vec<Slot>>;
 
+/// An anomaly in the metadata of a slot, as reported by
+/// [`Blockstore::run_consistency_check`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SlotInconsistency {
+    /// The slot claims more consecutive shreds than the highest shred received.
+    ConsumedExceedsReceived {
+        slot: Slot,
+        consumed: u64,
+        received: u64,
+    },
+    /// A shred marked data complete lies beyond the highest shred received.
+    CompletedDataIndexOutOfRange {
+        slot: Slot,
+        index: u32,
+        received: u64,
+    },
+    /// The slot meta can't be deserialized.
+    UnreadableSlotMeta { slot: Slot, error: String },
+}
+
+/// A signature of a transaction that referenced an address, as returned by
+/// [`Blockstore::get_confirmed_signatures_for_address`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressSignature {
+    pub slot: Slot,
+    pub signature: Signature,
+    /// Whether the transaction locked the address as writable
+    pub writeable: bool,
+}
+
 // ledger window
 pub struct Blockstore {
     ledger_path: PathBuf,
@@ -75,6 +217,7 @@ pub struct Blockstore {
     data_shred_cf: LedgerColumn<cf::ShredData>,
     dead_slots_cf: LedgerColumn<cf::DeadSlots>,
     erasure_meta_cf: LedgerColumn<cf::ErasureMeta>,
+    genesis_ticks_cf: LedgerColumn<cf::GenesisTicks>,
     index_cf: LedgerColumn<cf::Index>,
     merkle_root_meta_cf: LedgerColumn<cf::MerkleRootMeta>,
     meta_cf: LedgerColumn<cf::SlotMeta>,
@@ -88,6 +231,7 @@ pub struct Blockstore {
     transaction_status_index_cf: LedgerColumn<cf::TransactionStatusIndex>,
 
     highest_primary_index_slot: RwLock<Option<Slot>>,
+    transaction_status_index_lock: Mutex<()>,
     max_root: AtomicU64,
     insert_shreds_lock: Mutex<()>,
     new_shreds_signals: Mutex<Vec<Sender<bool>>>,
@@ -107,22 +251,65 @@ impl Blockstore {
         Self::do_open(ledger_path, options)
     }
 
+    /// Opens a Ledger in directory with secondary (read only) access, which is
+    /// what tools inspecting a ledger typically need
+    pub fn open_secondary(ledger_path: &Path) -> Result<Blockstore> {
+        Self::do_open(
+            ledger_path,
+            BlockstoreOptions {
+                enforce_ulimit_nofile: false,
+                ..BlockstoreOptions::with_access_type(AccessType::Secondary)
+            },
+        )
+    }
+
     /// Deletes the blockstore at the specified path.
     ///
     /// Note that if the `ledger_path` has multiple rocksdb instances, this
     /// function will destroy all.
+    ///
+    /// Fails with [`BlockstoreError::AlreadyInUse`] if the blockstore is open
+    /// in primary mode.
     pub fn destroy(ledger_path: &Path) -> Result<()> {
         // Database::destroy() fails if the root directory doesn't exist
         fs::create_dir_all(ledger_path)?;
         Rocks::destroy(&Path::new(ledger_path).join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL))
     }
 
+    /// Deletes the blockstore at the specified path, but only if none of its
+    /// columns contain data. Returns whether the blockstore was destroyed.
+    pub fn destroy_if_empty(ledger_path: &Path) -> Result<bool> {
+        let blockstore_path = ledger_path.join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL);
+        if blockstore_path.exists() && !Rocks::is_empty(&blockstore_path)? {
+            info!("Refusing to destroy non-empty blockstore at {blockstore_path:?}");
+            return Ok(false);
+        }
+        Self::destroy(ledger_path)?;
+        Ok(true)
+    }
+
+    /// Returns the paths that [`Blockstore::destroy`] would remove for the
+    /// specified ledger path, without removing anything.
+    pub fn would_destroy_paths(ledger_path: &Path) -> Vec<PathBuf> {
+        let blockstore_path = ledger_path.join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL);
+        if blockstore_path.exists() {
+            vec![blockstore_path]
+        } else {
+            vec![]
+        }
+    }
+
     fn do_open(ledger_path: &Path, options: BlockstoreOptions) -> Result<Blockstore> {
         fs::create_dir_all(ledger_path)?;
         let blockstore_path = ledger_path.join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL);
 
         //adjust_ulimit_nofile(options.enforce_ulimit_nofile)?;
 
+        let slots_stats = options
+            .slots_stats_capacity
+            .map(SlotsStats::with_capacity)
+            .unwrap_or_default();
+
         // Open the database
         let mut measure = Measure::start("blockstore open");
         info!("Opening blockstore at {blockstore_path:?}");
@@ -136,6 +323,7 @@ impl Blockstore {
         let data_shred_cf = db.column();
         let dead_slots_cf = db.column();
         let erasure_meta_cf = db.column();
+        let genesis_ticks_cf = db.column();
         let index_cf = db.column();
         let merkle_root_meta_cf = db.column();
         let meta_cf = db.column();
@@ -169,6 +357,7 @@ impl Blockstore {
             data_shred_cf,
             dead_slots_cf,
             erasure_meta_cf,
+            genesis_ticks_cf,
             index_cf,
             merkle_root_meta_cf,
             meta_cf,
@@ -181,12 +370,13 @@ impl Blockstore {
             transaction_status_cf,
             transaction_status_index_cf,
             highest_primary_index_slot: RwLock::<Option<Slot>>::default(),
+            transaction_status_index_lock: Mutex::default(),
             new_shreds_signals: Mutex::default(),
             completed_slots_senders: Mutex::default(),
             insert_shreds_lock: Mutex::<()>::default(),
             max_root,
             lowest_cleanup_slot: RwLock::<Slot>::default(),
-            slots_stats: SlotsStats::default(),
+            slots_stats,
             rpc_api_metrics: BlockstoreRpcApiMetrics::default(),
         };
         blockstore.cleanup_old_entries()?;
@@ -195,11 +385,550 @@ impl Blockstore {
         Ok(blockstore)
     }
 
+    /// Registers a signal that receives `true` whenever new shreds are inserted.
+    pub fn add_new_shred_signal(&self, sender: Sender<bool>) {
+        self.new_shreds_signals.lock().unwrap().push(sender);
+    }
+
+    /// Registers a signal that receives the slots completed by each shred
+    /// insertion.
+    pub fn add_completed_slots_signal(&self, sender: CompletedSlotsSender) {
+        self.completed_slots_senders.lock().unwrap().push(sender);
+    }
+
+    /// Unregisters every signal added by [`Self::add_new_shred_signal`] and
+    /// [`Self::add_completed_slots_signal`].
+    pub fn clear_signals(&self) {
+        self.new_shreds_signals.lock().unwrap().clear();
+        self.completed_slots_senders.lock().unwrap().clear();
+    }
+
+    /// Notifies the registered signals after new entries were inserted and
+    /// `newly_completed_slots` became full.
+    fn send_signals(&self, should_signal: bool, newly_completed_slots: Vec<Slot>) {
+        if should_signal {
+            for signal in self.new_shreds_signals.lock().unwrap().iter() {
+                match signal.try_send(true) {
+                    Ok(_) => {}
+                    Err(TrySendError::Full(_)) => {
+                        trace!("replay wake up signal channel is full.")
+                    }
+                    Err(TrySendError::Disconnected(_)) => {
+                        trace!("replay wake up signal channel is disconnected.")
+                    }
+                }
+            }
+        }
+
+        if newly_completed_slots.is_empty() {
+            return;
+        }
+        for signal in self.completed_slots_senders.lock().unwrap().iter() {
+            if let Err(TrySendError::Full(_)) = signal.try_send(newly_completed_slots.clone()) {
+                trace!("completed slots signal channel is full.")
+            }
+        }
+    }
+
     /// Returns whether the blockstore has primary (read and write) access
     pub fn is_primary_access(&self) -> bool {
         self.db.is_primary_access()
     }
 
+    /// Returns the slot meta of `slot`, if any.
+    pub fn meta(&self, slot: Slot) -> Result<Option<SlotMeta>> {
+        self.meta_cf.get(slot)
+    }
+
+    /// Stores the slot meta of `slot`, overwriting any existing one.
+    #[cfg(feature = "dev-context-only-utils")]
+    pub fn put_meta(&self, slot: Slot, meta: &SlotMeta) -> Result<()> {
+        self.meta_cf.put(slot, meta)
+    }
+
+    /// Returns the index of the data and coding shreds received for `slot`, if
+    /// any.
+    pub fn get_index(&self, slot: Slot) -> Result<Option<Index>> {
+        self.index_cf.get(slot)
+    }
+
+    /// Stores the shred index of `slot`, overwriting any existing one.
+    #[cfg(feature = "dev-context-only-utils")]
+    pub fn put_index(&self, slot: Slot, index: &Index) -> Result<()> {
+        self.index_cf.put(slot, index)
+    }
+
+    /// Returns an iterator over the slot metas starting from `slot` and
+    /// walking in the given direction. A slot meta that can't be deserialized
+    /// is yielded as a [`BlockstoreError::ColumnValue`].
+    pub fn slot_meta_iter(
+        &self,
+        slot: Slot,
+        direction: IteratorDirection,
+    ) -> Result<impl Iterator<Item = Result<(Slot, SlotMeta)>> + '_> {
+        self.meta_cf
+            .iter_deserialized(IteratorMode::From(slot, direction))
+    }
+
+    /// Returns whether `slot` has a slot meta, i.e. whether anything about it
+    /// has been stored.
+    pub fn slot_exists(&self, slot: Slot) -> Result<bool> {
+        Ok(self.meta_cf.get_bytes(slot)?.is_some())
+    }
+
+    /// Returns the lowest slot past genesis for which shreds have been
+    /// received, or the max root if there is no such slot.
+    pub fn lowest_slot(&self) -> Result<Slot> {
+        for item in self.slot_meta_iter(0, IteratorDirection::Forward)? {
+            let (slot, slot_meta) = item?;
+            if slot > 0 && slot_meta.received > 0 {
+                return Ok(slot);
+            }
+        }
+        // Only an empty blockstore gets here, e.g. right after boot.
+        Ok(self.max_root.load(Ordering::Relaxed))
+    }
+
+    /// Returns the highest slot with a slot meta, if any.
+    pub fn highest_slot(&self) -> Result<Option<Slot>> {
+        Ok(self
+            .meta_cf
+            .iter(IteratorMode::End)?
+            .next()
+            .map(|(slot, _)| slot))
+    }
+
+    /// Returns an iterator over the rooted slots starting from `slot` and
+    /// walking in the given direction.
+    pub fn rooted_slot_iterator(
+        &self,
+        slot: Slot,
+        direction: IteratorDirection,
+    ) -> Result<impl Iterator<Item = Slot> + '_> {
+        let slot_iterator = self.roots_cf.iter(IteratorMode::From(slot, direction))?;
+        Ok(slot_iterator.map(|(rooted_slot, _)| rooted_slot))
+    }
+
+    /// Marks the given slots as rooted.
+    pub fn set_roots<'a>(&self, rooted_slots: impl Iterator<Item = &'a Slot>) -> Result<()> {
+        let mut max_new_rooted_slot = 0;
+        for slot in rooted_slots {
+            max_new_rooted_slot = std::cmp::max(max_new_rooted_slot, *slot);
+            self.roots_cf.put(*slot, &true)?;
+        }
+        self.max_root
+            .fetch_max(max_new_rooted_slot, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Stores the ticks of slot 0 and marks the slot full.
+    ///
+    /// Until the ledger has a shredder, the ticks are kept in the genesis
+    /// ticks column instead of being stored as data shreds.
+    fn insert_genesis_ticks(&self, ticks: &[Entry]) -> Result<()> {
+        self.genesis_ticks_cf.put(0, &ticks.to_vec())?;
+        let num_ticks = ticks.len() as u64;
+        let slot_meta = SlotMeta {
+            slot: 0,
+            consumed: num_ticks,
+            received: num_ticks,
+            last_index: num_ticks.checked_sub(1),
+            parent_slot: Some(0),
+            completed_data_indexes: num_ticks
+                .checked_sub(1)
+                .map(|last_index| last_index as u32)
+                .into_iter()
+                .collect(),
+            ..SlotMeta::default()
+        };
+        self.meta_cf.put(0, &slot_meta)?;
+        self.send_signals(true, vec![0]);
+        Ok(())
+    }
+
+    /// Returns the ticks of slot 0, as stored by
+    /// [`Blockstore::insert_genesis_ticks`].
+    fn get_genesis_ticks(&self) -> Result<Vec<Entry>> {
+        Ok(self.genesis_ticks_cf.get(0)?.unwrap_or_default())
+    }
+
+    pub fn is_root(&self, slot: Slot) -> bool {
+        matches!(self.roots_cf.get(slot), Ok(Some(true)))
+    }
+
+    /// Marks `slot` as dead, e.g. because it failed to replay.
+    pub fn set_dead_slot(&self, slot: Slot) -> Result<()> {
+        self.dead_slots_cf.put(slot, &true)?;
+        self.slots_stats.mark_dead(slot);
+        Ok(())
+    }
+
+    /// Returns whether `slot` has been marked dead.
+    pub fn is_dead(&self, slot: Slot) -> Result<bool> {
+        Ok(self.dead_slots_cf.get(slot)?.unwrap_or(false))
+    }
+
+    /// Returns an iterator over the slots marked dead starting from `slot` and
+    /// walking in the given direction.
+    pub fn dead_slots_iterator(
+        &self,
+        slot: Slot,
+        direction: IteratorDirection,
+    ) -> Result<impl Iterator<Item = Slot> + '_> {
+        let dead_slots_iterator = self
+            .dead_slots_cf
+            .iter(IteratorMode::From(slot, direction))?;
+        Ok(dead_slots_iterator.map(|(slot, _)| slot))
+    }
+
+    /// Checks the metadata of every slot for inconsistencies, such as those a
+    /// crash in the middle of a write could leave behind.
+    ///
+    /// Anomalies, including slot metas that can't be deserialized, are reported
+    /// rather than treated as errors; an error is only returned if the meta
+    /// column cannot be iterated.
+    pub fn run_consistency_check(&self) -> Result<Vec<SlotInconsistency>> {
+        let mut inconsistencies = vec![];
+        for (slot, slot_meta_bytes) in self.meta_cf.iter(IteratorMode::Start)? {
+            let slot_meta = match cf::SlotMeta::deserialize(&slot_meta_bytes) {
+                Ok(slot_meta) => slot_meta,
+                Err(err) => {
+                    inconsistencies.push(SlotInconsistency::UnreadableSlotMeta {
+                        slot,
+                        error: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            if slot_meta.consumed > slot_meta.received {
+                inconsistencies.push(SlotInconsistency::ConsumedExceedsReceived {
+                    slot,
+                    consumed: slot_meta.consumed,
+                    received: slot_meta.received,
+                });
+            }
+            // received is the index *plus one* of the highest shred received
+            inconsistencies.extend(
+                slot_meta
+                    .completed_data_indexes
+                    .iter()
+                    .filter(|&&index| u64::from(index) >= slot_meta.received)
+                    .map(|&index| SlotInconsistency::CompletedDataIndexOutOfRange {
+                        slot,
+                        index,
+                        received: slot_meta.received,
+                    }),
+            );
+        }
+        Ok(inconsistencies)
+    }
+
+    /// Flushes every column to disk and syncs the write-ahead log, e.g.
+    /// before taking a snapshot or shutting down.
+    ///
+    /// A secondary instance never writes, so this is a no-op without primary
+    /// access.
+    pub fn flush(&self) -> Result<()> {
+        if !self.is_primary_access() {
+            return Ok(());
+        }
+        self.db.flush()
+    }
+
+    /// Creates a consistent, point-in-time copy of the blockstore under
+    /// `checkpoint_path` without stopping writers. The copy is laid out like a
+    /// ledger, so it can be opened with [`Blockstore::open_secondary`] on
+    /// `checkpoint_path`.
+    ///
+    /// Requires primary access.
+    pub fn create_checkpoint(&self, checkpoint_path: &Path) -> Result<()> {
+        if !self.is_primary_access() {
+            return Err(BlockstoreError::PrimaryAccessRequired);
+        }
+        fs::create_dir_all(checkpoint_path)?;
+        self.db
+            .create_checkpoint(&checkpoint_path.join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL))
+    }
+
+    /// Manually compacts every slot-keyed column over the slots in the
+    /// inclusive range `[from_slot, to_slot]`, reclaiming the space held by
+    /// deleted keys.
+    ///
+    /// Compaction modifies the database, so this is a no-op unless the
+    /// blockstore was opened with primary access. Returns whether the
+    /// compaction ran.
+    pub fn compact_slots(&self, from_slot: Slot, to_slot: Slot) -> Result<bool> {
+        if !self.is_primary_access() {
+            info!(
+                "Skipping manual compaction of slots {from_slot}..={to_slot} without primary \
+                 access"
+            );
+            return Ok(false);
+        }
+
+        let mut measure = Measure::start("compact slots");
+        self.meta_cf.compact_range(from_slot, to_slot);
+        self.bank_hash_cf.compact_range(from_slot, to_slot);
+        self.block_height_cf.compact_range(from_slot, to_slot);
+        self.blocktime_cf.compact_range(from_slot, to_slot);
+        self.code_shred_cf.compact_range(from_slot, to_slot);
+        self.data_shred_cf.compact_range(from_slot, to_slot);
+        self.dead_slots_cf.compact_range(from_slot, to_slot);
+        self.erasure_meta_cf.compact_range(from_slot, to_slot);
+        self.genesis_ticks_cf.compact_range(from_slot, to_slot);
+        self.index_cf.compact_range(from_slot, to_slot);
+        self.merkle_root_meta_cf.compact_range(from_slot, to_slot);
+        self.optimistic_slots_cf.compact_range(from_slot, to_slot);
+        self.orphans_cf.compact_range(from_slot, to_slot);
+        self.perf_samples_cf.compact_range(from_slot, to_slot);
+        self.rewards_cf.compact_range(from_slot, to_slot);
+        self.roots_cf.compact_range(from_slot, to_slot);
+        measure.stop();
+        info!("Compacted slots {from_slot}..={to_slot}; {measure}");
+
+        Ok(true)
+    }
+
+    /// Stores the status of the transaction identified by `signature` that was
+    /// executed in `slot`.
+    pub fn write_transaction_status(
+        &self,
+        slot: Slot,
+        signature: Signature,
+        status: &TransactionStatusMeta,
+    ) -> Result<()> {
+        self.transaction_status_cf.put((signature, slot), status)?;
+        self.update_transaction_status_index(slot)
+    }
+
+    /// Returns the status of the transaction identified by `signature`, along
+    /// with the slot it was executed in. If the transaction was recorded in
+    /// several slots, the status from the lowest slot is returned.
+    pub fn read_transaction_status(
+        &self,
+        signature: Signature,
+    ) -> Result<Option<(Slot, TransactionStatusMeta)>> {
+        self.rpc_api_metrics
+            .num_get_transaction_status
+            .fetch_add(1, Ordering::Relaxed);
+
+        let mut iterator = self.transaction_status_cf.iter(IteratorMode::From(
+            (signature, 0),
+            IteratorDirection::Forward,
+        ))?;
+        let Some(((found_signature, slot), data)) = iterator.next() else {
+            return Ok(None);
+        };
+        if found_signature != signature {
+            return Ok(None);
+        }
+        let status = cf::TransactionStatus::deserialize(&data)?;
+        Ok(Some((slot, status)))
+    }
+
+    /// Records that the transaction identified by `signature`, executed in
+    /// `slot`, referenced `address`.
+    pub fn write_address_signature(
+        &self,
+        address: Pubkey,
+        slot: Slot,
+        signature: Signature,
+        writeable: bool,
+    ) -> Result<()> {
+        // Transaction indices are not tracked, so signatures of the same slot
+        // are ordered by the signature itself
+        self.address_signatures_cf.put(
+            (address, slot, 0, signature),
+            &AddressSignatureMeta { writeable },
+        )
+    }
+
+    /// Returns up to `limit` signatures of transactions that referenced
+    /// `address`, newest slot first.
+    ///
+    /// If `before` is given, the search starts right after that signature;
+    /// nothing is returned if `before` never referenced `address`. If `until`
+    /// is given, the search stops right before that signature.
+    pub fn get_confirmed_signatures_for_address(
+        &self,
+        address: Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<AddressSignature>> {
+        self.rpc_api_metrics
+            .num_get_confirmed_signatures_for_address
+            .fetch_add(1, Ordering::Relaxed);
+
+        let iterator = self.address_signatures_cf.iter(IteratorMode::From(
+            (
+                address,
+                Slot::MAX,
+                u32::MAX,
+                Signature::from([u8::MAX; SIGNATURE_BYTES]),
+            ),
+            IteratorDirection::Reverse,
+        ))?;
+        let mut found_before = before.is_none();
+        let mut signatures = Vec::new();
+        for ((key_address, slot, _transaction_index, signature), data) in iterator {
+            if key_address != address || signatures.len() >= limit {
+                break;
+            }
+            if !found_before {
+                found_before = before == Some(signature);
+                continue;
+            }
+            if until == Some(signature) {
+                break;
+            }
+            let meta = cf::AddressSignatures::deserialize(&data)?;
+            signatures.push(AddressSignature {
+                slot,
+                signature,
+                writeable: meta.writeable,
+            });
+        }
+        Ok(signatures)
+    }
+
+    /// Stores the performance sample taken at `slot`.
+    pub fn write_perf_sample(&self, slot: Slot, perf_sample: &PerfSample) -> Result<()> {
+        self.perf_samples_cf.put(slot, perf_sample)
+    }
+
+    /// Returns up to `limit` performance samples, most recent slot first.
+    pub fn get_recent_perf_samples(&self, limit: usize) -> Result<Vec<(Slot, PerfSample)>> {
+        self.perf_samples_cf
+            .iter(IteratorMode::End)?
+            .take(limit)
+            .map(|(slot, data)| Ok((slot, cf::PerfSamples::deserialize(&data)?)))
+            .collect()
+    }
+
+    /// Records that `slot`, with bank hash `hash`, was optimistically
+    /// confirmed at `timestamp`.
+    pub fn insert_optimistic_slot(
+        &self,
+        slot: Slot,
+        hash: &Hash,
+        timestamp: UnixTimestamp,
+    ) -> Result<()> {
+        let slot_data = OptimisticSlotMetaVersioned::new(*hash, timestamp);
+        self.optimistic_slots_cf.put(slot, &slot_data)
+    }
+
+    /// Returns the `num` most recent optimistically confirmed slots, with their
+    /// hashes and timestamps, highest slot first.
+    pub fn get_latest_optimistic_slots(
+        &self,
+        num: usize,
+    ) -> Result<Vec<(Slot, Hash, UnixTimestamp)>> {
+        self.optimistic_slots_cf
+            .iter(IteratorMode::End)?
+            .take(num)
+            .map(|(slot, data)| {
+                let meta = cf::OptimisticSlots::deserialize(&data)?;
+                Ok((slot, meta.hash(), meta.timestamp()))
+            })
+            .collect()
+    }
+
+    /// Stores the rewards credited in `slot`, such as the staker and voter
+    /// portions of each stake reward.
+    pub fn write_rewards(&self, slot: Slot, rewards: &Rewards) -> Result<()> {
+        self.rewards_cf.put(slot, rewards)
+    }
+
+    /// Returns the rewards credited in `slot`, if any were stored.
+    pub fn get_rewards(&self, slot: Slot) -> Result<Option<Rewards>> {
+        self.rewards_cf.get(slot)
+    }
+
+    /// Records the block height of `slot`.
+    pub fn set_block_height(&self, slot: Slot, block_height: u64) -> Result<()> {
+        self.block_height_cf.put(slot, &block_height)
+    }
+
+    /// Returns the block height of `slot`, if it is known.
+    pub fn get_block_height(&self, slot: Slot) -> Result<Option<u64>> {
+        self.rpc_api_metrics
+            .num_get_block_height
+            .fetch_add(1, Ordering::Relaxed);
+        self.block_height_cf.get(slot)
+    }
+
+    /// Returns the block height of the highest slot with a known block height.
+    ///
+    /// Block heights grow with slots, so this is also the highest block height.
+    pub fn max_block_height(&self) -> Result<Option<u64>> {
+        self.block_height_cf
+            .iter(IteratorMode::End)?
+            .next()
+            .map(|(_slot, data)| cf::BlockHeight::deserialize(&data))
+            .transpose()
+    }
+
+    /// Records the bank hash `slot` was frozen with, and whether that hash has
+    /// been duplicate confirmed.
+    pub fn insert_bank_hash(
+        &self,
+        slot: Slot,
+        frozen_hash: Hash,
+        is_duplicate_confirmed: bool,
+    ) -> Result<()> {
+        if let Some(prev_value) = self.bank_hash_cf.get(slot)? {
+            if prev_value.frozen_hash() == frozen_hash && prev_value.is_duplicate_confirmed() {
+                // Don't overwrite is_duplicate_confirmed == true with is_duplicate_confirmed == false,
+                // which may happen on startup when procesing from blockstore processor because the
+                // blocks may not reflect earlier observed gossip votes from before the restart.
+                return Ok(());
+            }
+        }
+        let data = FrozenHashVersioned::Current(FrozenHashStatus {
+            frozen_hash,
+            is_duplicate_confirmed,
+        });
+        self.bank_hash_cf.put(slot, &data)
+    }
+
+    /// Returns the bank hash `slot` was frozen with, if any.
+    pub fn get_bank_hash(&self, slot: Slot) -> Result<Option<Hash>> {
+        Ok(self
+            .bank_hash_cf
+            .get(slot)?
+            .map(|versioned| versioned.frozen_hash()))
+    }
+
+    /// Returns whether the bank hash of `slot` has been duplicate confirmed.
+    pub fn is_duplicate_confirmed(&self, slot: Slot) -> Result<bool> {
+        Ok(self
+            .bank_hash_cf
+            .get(slot)?
+            .is_some_and(|versioned| versioned.is_duplicate_confirmed()))
+    }
+
+    /// Raises `max_slot` of the active (not frozen) transaction status index
+    /// to `slot`.
+    fn update_transaction_status_index(&self, slot: Slot) -> Result<()> {
+        let _lock = self.transaction_status_index_lock.lock().unwrap();
+        for index in 0..=1 {
+            let Some(mut index_meta) = self.transaction_status_index_cf.get(index)? else {
+                continue;
+            };
+            if index_meta.frozen {
+                continue;
+            }
+            if slot > index_meta.max_slot {
+                index_meta.max_slot = slot;
+                self.transaction_status_index_cf.put(index, &index_meta)?;
+            }
+            self.set_highest_primary_index_slot(slot);
+            break;
+        }
+        Ok(())
+    }
+
     fn cleanup_old_entries(&self) -> Result<()> {
         if !self.is_primary_access() {
             return Ok(());
@@ -232,22 +961,1090 @@ impl Blockstore {
         let iterator = self.transaction_status_index_cf.iter(IteratorMode::Start)?;
         let mut highest_primary_index_slot = None;
         for (_, data) in iterator {
-            let meta: TransactionStatusIndexMeta = deserialize(&data).unwrap();
+            let meta = cf::TransactionStatusIndex::deserialize(&data)?;
             if highest_primary_index_slot.is_none()
                 || highest_primary_index_slot.is_some_and(|slot| slot < meta.max_slot)
             {
                 highest_primary_index_slot = Some(meta.max_slot);
             }
         }
-        if highest_primary_index_slot.is_some_and(|slot| slot != 0) {
-            self.set_highest_primary_index_slot(highest_primary_index_slot);
-        } else {
-            self.db.set_clean_slot_0(true);
+        match highest_primary_index_slot {
+            Some(slot) if slot != 0 => self.set_highest_primary_index_slot(slot),
+            _ => self.db.set_clean_slot_0(true),
         }
         Ok(())
     }
 
-    fn set_highest_primary_index_slot(&self, slot: Option<Slot>) {
-        *self.highest_primary_index_slot.write().unwrap() = slot;
+    /// Returns the highest slot tracked by the primary transaction status
+    /// index, if any.
+    pub fn highest_primary_index_slot(&self) -> Option<Slot> {
+        *self.highest_primary_index_slot.read().unwrap()
+    }
+
+    /// Raises the highest primary index slot to `slot`. The value never moves
+    /// backwards, so concurrent writers can't regress it.
+    fn set_highest_primary_index_slot(&self, slot: Slot) {
+        let mut highest_primary_index_slot = self.highest_primary_index_slot.write().unwrap();
+        if highest_primary_index_slot.is_none_or(|highest| highest < slot) {
+            *highest_primary_index_slot = Some(slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockstore::column::ColumnName;
+    use crate::blockstore_meta::CompletedDataIndexes;
+    use crate::shred::MAX_DATA_SHREDS_PER_SLOT;
+    use blockchain_transaction_status_client_types::Reward;
+    use rocksdb::{Options, DB};
+    use solana_reward_info::RewardType;
+    use std::num::NonZeroUsize;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compact_slots() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        for slot in 0..100 {
+            blockstore.roots_cf.put(slot, &true).unwrap();
+        }
+        let num_keys_before = blockstore
+            .roots_cf
+            .get_int_property(RocksProperties::ESTIMATE_NUM_KEYS)
+            .unwrap();
+        assert!(num_keys_before > 0);
+
+        for slot in 0..50 {
+            blockstore.roots_cf.delete(slot).unwrap();
+        }
+        assert!(blockstore.compact_slots(0, 49).unwrap());
+
+        let num_keys_after = blockstore
+            .roots_cf
+            .get_int_property(RocksProperties::ESTIMATE_NUM_KEYS)
+            .unwrap();
+        assert!(num_keys_after < num_keys_before);
+        assert_eq!(
+            blockstore
+                .roots_cf
+                .iter(IteratorMode::Start)
+                .unwrap()
+                .count(),
+            50
+        );
+    }
+
+    #[test]
+    fn test_compact_slots_requires_primary_access() {
+        let ledger_path = TempDir::new().unwrap();
+        // Create the database so that it can be opened as a secondary
+        drop(Blockstore::open(ledger_path.path()).unwrap());
+
+        let blockstore = Blockstore::open_with_options(
+            ledger_path.path(),
+            BlockstoreOptions::with_access_type(AccessType::Secondary),
+        )
+        .unwrap();
+        assert!(!blockstore.compact_slots(0, 100).unwrap());
+    }
+
+    #[test]
+    fn test_destroy_if_empty_empty_ledger() {
+        let ledger_path = TempDir::new().unwrap();
+        drop(Blockstore::open(ledger_path.path()).unwrap());
+
+        let blockstore_path = ledger_path.path().join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL);
+        assert_eq!(
+            Blockstore::would_destroy_paths(ledger_path.path()),
+            vec![blockstore_path.clone()]
+        );
+        assert!(Blockstore::destroy_if_empty(ledger_path.path()).unwrap());
+        assert!(!blockstore_path.exists());
+        assert!(Blockstore::would_destroy_paths(ledger_path.path()).is_empty());
+    }
+
+    #[test]
+    fn test_destroy_if_empty_populated_ledger() {
+        let ledger_path = TempDir::new().unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            blockstore.roots_cf.put(1, &true).unwrap();
+        }
+
+        assert!(!Blockstore::destroy_if_empty(ledger_path.path()).unwrap());
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert_eq!(blockstore.roots_cf.get(1).unwrap(), Some(true));
+    }
+
+    #[test]
+    fn test_destroy_ledger_in_use() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        blockstore.roots_cf.put(1, &true).unwrap();
+
+        assert!(matches!(
+            Blockstore::destroy(ledger_path.path()),
+            Err(BlockstoreError::AlreadyInUse(_))
+        ));
+        assert_eq!(blockstore.roots_cf.get(1).unwrap(), Some(true));
+
+        drop(blockstore);
+        Blockstore::destroy(ledger_path.path()).unwrap();
+        assert!(Blockstore::destroy_if_empty(ledger_path.path()).unwrap());
+    }
+
+    #[test]
+    fn test_open_primary_twice() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        assert!(matches!(
+            Blockstore::open(ledger_path.path()),
+            Err(BlockstoreError::AlreadyInUse(path))
+                if path == ledger_path.path().join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL)
+        ));
+        // Secondary access is still allowed
+        Blockstore::open_secondary(ledger_path.path()).unwrap();
+
+        drop(blockstore);
+        Blockstore::open(ledger_path.path()).unwrap();
+    }
+
+    #[test]
+    fn test_rooted_slot_iterator() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let roots = [2, 4, 7, 12, 15];
+        for root in roots {
+            blockstore.roots_cf.put(root, &true).unwrap();
+        }
+
+        let forward: Vec<_> = blockstore
+            .rooted_slot_iterator(0, IteratorDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(forward, roots);
+        let forward: Vec<_> = blockstore
+            .rooted_slot_iterator(7, IteratorDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(forward, [7, 12, 15]);
+
+        let reverse: Vec<_> = blockstore
+            .rooted_slot_iterator(12, IteratorDirection::Reverse)
+            .unwrap()
+            .collect();
+        assert_eq!(reverse, [12, 7, 4, 2]);
+        let reverse: Vec<_> = blockstore
+            .rooted_slot_iterator(u64::MAX, IteratorDirection::Reverse)
+            .unwrap()
+            .collect();
+        assert_eq!(reverse, [15, 12, 7, 4, 2]);
+    }
+
+    #[test]
+    fn test_slot_meta_and_dead_slots_iterators() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        for slot in [1, 3, 5] {
+            let slot_meta = SlotMeta {
+                slot,
+                ..SlotMeta::default()
+            };
+            blockstore.meta_cf.put(slot, &slot_meta).unwrap();
+            blockstore.dead_slots_cf.put(slot + 1, &true).unwrap();
+        }
+
+        let slot_metas: Vec<_> = blockstore
+            .slot_meta_iter(3, IteratorDirection::Forward)
+            .unwrap()
+            .map(|item| {
+                let (slot, slot_meta) = item.unwrap();
+                assert_eq!(slot, slot_meta.slot);
+                slot
+            })
+            .collect();
+        assert_eq!(slot_metas, [3, 5]);
+        let slot_metas: Vec<_> = blockstore
+            .slot_meta_iter(3, IteratorDirection::Reverse)
+            .unwrap()
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(slot_metas, [3, 1]);
+
+        let dead_slots: Vec<_> = blockstore
+            .dead_slots_iterator(0, IteratorDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(dead_slots, [2, 4, 6]);
+        let dead_slots: Vec<_> = blockstore
+            .dead_slots_iterator(5, IteratorDirection::Reverse)
+            .unwrap()
+            .collect();
+        assert_eq!(dead_slots, [4, 2]);
+    }
+
+    #[test]
+    fn test_slot_meta_iter_corrupt_meta() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        for slot in [1, 3] {
+            let slot_meta = SlotMeta {
+                slot,
+                ..SlotMeta::default()
+            };
+            blockstore.meta_cf.put(slot, &slot_meta).unwrap();
+        }
+        blockstore.meta_cf.put_bytes(2, &[1, 2, 3]).unwrap();
+
+        let mut slot_metas = blockstore
+            .slot_meta_iter(0, IteratorDirection::Forward)
+            .unwrap();
+        assert_eq!(slot_metas.next().unwrap().unwrap().0, 1);
+        assert!(matches!(
+            slot_metas.next().unwrap(),
+            Err(BlockstoreError::ColumnValue {
+                column: cf::SlotMeta::NAME,
+                ..
+            })
+        ));
+        // The corrupt meta doesn't end the iteration
+        assert_eq!(slot_metas.next().unwrap().unwrap().0, 3);
+        assert!(slot_metas.next().is_none());
+    }
+
+    #[test]
+    fn test_flush() {
+        let ledger_path = TempDir::new().unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            blockstore.set_roots([3].iter()).unwrap();
+            blockstore.set_block_height(3, 2).unwrap();
+            blockstore.flush().unwrap();
+        }
+
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert!(blockstore.is_root(3));
+        assert_eq!(blockstore.get_block_height(3).unwrap(), Some(2));
+
+        let secondary = Blockstore::open_secondary(ledger_path.path()).unwrap();
+        secondary.flush().unwrap();
+    }
+
+    #[test]
+    fn test_create_checkpoint() {
+        let ledger_path = TempDir::new().unwrap();
+        let checkpoint_dir = TempDir::new().unwrap();
+        let checkpoint_path = checkpoint_dir.path().join("checkpoint");
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            blockstore.set_roots([1, 2].iter()).unwrap();
+            blockstore.set_block_height(2, 1).unwrap();
+            blockstore.create_checkpoint(&checkpoint_path).unwrap();
+            // writes after the checkpoint don't show up in it
+            blockstore.set_roots([3].iter()).unwrap();
+
+            let secondary = Blockstore::open_secondary(ledger_path.path()).unwrap();
+            assert!(matches!(
+                secondary.create_checkpoint(&checkpoint_dir.path().join("secondary")),
+                Err(BlockstoreError::PrimaryAccessRequired)
+            ));
+        }
+
+        let checkpoint = Blockstore::open_secondary(&checkpoint_path).unwrap();
+        let roots: Vec<_> = checkpoint
+            .rooted_slot_iterator(0, IteratorDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(roots, [1, 2]);
+        assert_eq!(checkpoint.get_block_height(2).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_slot_exists_and_slot_range() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert!(!blockstore.slot_exists(0).unwrap());
+        assert_eq!(blockstore.lowest_slot().unwrap(), 0);
+        assert_eq!(blockstore.highest_slot().unwrap(), None);
+
+        blockstore.set_roots([2].iter()).unwrap();
+        for (slot, received) in [(0, 1), (3, 0), (5, 2), (9, 1)] {
+            let slot_meta = SlotMeta {
+                slot,
+                received,
+                ..SlotMeta::default()
+            };
+            blockstore.meta_cf.put(slot, &slot_meta).unwrap();
+        }
+
+        assert!(blockstore.slot_exists(0).unwrap());
+        assert!(blockstore.slot_exists(3).unwrap());
+        assert!(!blockstore.slot_exists(4).unwrap());
+        // slot 0 is genesis and slot 3 has no shreds yet
+        assert_eq!(blockstore.lowest_slot().unwrap(), 5);
+        assert_eq!(blockstore.highest_slot().unwrap(), Some(9));
+
+        // A slot meta that can't be deserialized is an error, not skipped
+        blockstore.meta_cf.put_bytes(4, &[1, 2, 3]).unwrap();
+        assert!(blockstore.slot_exists(4).unwrap());
+        assert!(matches!(
+            blockstore.lowest_slot(),
+            Err(BlockstoreError::ColumnValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_meta_and_index() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert_eq!(blockstore.meta(3).unwrap(), None);
+        assert_eq!(blockstore.get_index(3).unwrap(), None);
+
+        let slot_meta = SlotMeta {
+            slot: 3,
+            received: 2,
+            ..SlotMeta::default()
+        };
+        blockstore.put_meta(3, &slot_meta).unwrap();
+        let mut index = Index::new(3);
+        index.data_mut().insert(0);
+        index.coding_mut().insert(1);
+        blockstore.put_index(3, &index).unwrap();
+
+        assert_eq!(blockstore.meta(3).unwrap(), Some(slot_meta));
+        assert_eq!(blockstore.get_index(3).unwrap(), Some(index));
+    }
+
+    #[test]
+    fn test_process_data_shred_flags() {
+        let mut slot_meta = SlotMeta::default();
+        // The last shred arrives before the shreds preceding it
+        process_data_shred_flags(5, ShredFlags::LAST_SHRED_IN_SLOT, &mut slot_meta).unwrap();
+        assert_eq!(slot_meta.last_index, Some(5));
+        process_data_shred_flags(1, ShredFlags::empty(), &mut slot_meta).unwrap();
+        process_data_shred_flags(2, ShredFlags::DATA_COMPLETE_SHRED, &mut slot_meta).unwrap();
+        assert_eq!(slot_meta.last_index, Some(5));
+        assert_eq!(
+            slot_meta.completed_data_indexes,
+            CompletedDataIndexes::from([2, 5])
+        );
+
+        // A conflicting last shred doesn't move last_index
+        process_data_shred_flags(7, ShredFlags::LAST_SHRED_IN_SLOT, &mut slot_meta).unwrap();
+        assert_eq!(slot_meta.last_index, Some(5));
+    }
+
+    #[test]
+    fn test_process_data_shred_flags_index_out_of_range() {
+        let mut slot_meta = SlotMeta {
+            slot: 3,
+            ..SlotMeta::default()
+        };
+        let max_index = MAX_DATA_SHREDS_PER_SLOT as u32 - 1;
+        process_data_shred_flags(max_index, ShredFlags::DATA_COMPLETE_SHRED, &mut slot_meta)
+            .unwrap();
+        assert_eq!(
+            slot_meta.completed_data_indexes,
+            CompletedDataIndexes::from([max_index])
+        );
+
+        let index = MAX_DATA_SHREDS_PER_SLOT as u32;
+        assert!(matches!(
+            process_data_shred_flags(index, ShredFlags::LAST_SHRED_IN_SLOT, &mut slot_meta),
+            Err(BlockstoreError::InvalidDataShredIndex(3, i)) if i == u64::from(index)
+        ));
+        assert_eq!(slot_meta.last_index, None);
+        assert_eq!(
+            slot_meta.completed_data_indexes,
+            CompletedDataIndexes::from([max_index])
+        );
+    }
+
+    #[test]
+    fn test_run_consistency_check() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let consistent = SlotMeta {
+            slot: 1,
+            consumed: 5,
+            received: 8,
+            completed_data_indexes: [4, 7].into(),
+            ..SlotMeta::default()
+        };
+        blockstore.meta_cf.put(1, &consistent).unwrap();
+        assert_eq!(blockstore.run_consistency_check().unwrap(), vec![]);
+
+        let corrupted = SlotMeta {
+            slot: 2,
+            consumed: 10,
+            received: 6,
+            completed_data_indexes: [3, 6, 9].into(),
+            ..SlotMeta::default()
+        };
+        blockstore.meta_cf.put(2, &corrupted).unwrap();
+        assert_eq!(
+            blockstore.run_consistency_check().unwrap(),
+            vec![
+                SlotInconsistency::ConsumedExceedsReceived {
+                    slot: 2,
+                    consumed: 10,
+                    received: 6,
+                },
+                SlotInconsistency::CompletedDataIndexOutOfRange {
+                    slot: 2,
+                    index: 6,
+                    received: 6,
+                },
+                SlotInconsistency::CompletedDataIndexOutOfRange {
+                    slot: 2,
+                    index: 9,
+                    received: 6,
+                },
+            ]
+        );
+
+        // A meta that can't be deserialized is reported, and doesn't stop the
+        // check
+        blockstore.meta_cf.put_bytes(0, &[1, 2, 3]).unwrap();
+        let inconsistencies = blockstore.run_consistency_check().unwrap();
+        assert_eq!(inconsistencies.len(), 4);
+        assert!(matches!(
+            &inconsistencies[0],
+            SlotInconsistency::UnreadableSlotMeta { slot: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn test_new_shreds_and_completed_slots_signals() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let (new_shreds_sender, new_shreds_receiver) = crossbeam_channel::bounded(1);
+        let (completed_slots_sender, completed_slots_receiver) = crossbeam_channel::unbounded();
+        blockstore.add_new_shred_signal(new_shreds_sender);
+        blockstore.add_completed_slots_signal(completed_slots_sender);
+
+        // An insertion that completes slots 1 and 2
+        blockstore.send_signals(true, vec![1, 2]);
+        assert_eq!(new_shreds_receiver.try_recv(), Ok(true));
+        assert_eq!(completed_slots_receiver.try_recv(), Ok(vec![1, 2]));
+
+        // An insertion that completes no slots only wakes up the new shreds signal
+        blockstore.send_signals(true, vec![]);
+        assert_eq!(new_shreds_receiver.try_recv(), Ok(true));
+        assert!(completed_slots_receiver.try_recv().is_err());
+
+        blockstore.clear_signals();
+        blockstore.send_signals(true, vec![3]);
+        assert!(new_shreds_receiver.try_recv().is_err());
+        assert!(completed_slots_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_insert_genesis_ticks_signals() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let (new_shreds_sender, new_shreds_receiver) = crossbeam_channel::bounded(1);
+        let (completed_slots_sender, completed_slots_receiver) = crossbeam_channel::unbounded();
+        blockstore.add_new_shred_signal(new_shreds_sender);
+        blockstore.add_completed_slots_signal(completed_slots_sender);
+
+        let ticks = create_ticks(4, 1, Hash::new_unique());
+        blockstore.insert_genesis_ticks(&ticks).unwrap();
+        assert_eq!(new_shreds_receiver.try_recv(), Ok(true));
+        assert_eq!(completed_slots_receiver.try_recv(), Ok(vec![0]));
+    }
+
+    #[test]
+    fn test_highest_primary_index_slot() {
+        let ledger_path = TempDir::new().unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            // Only the default index entries exist, so nothing is tracked yet
+            assert_eq!(blockstore.highest_primary_index_slot(), None);
+
+            blockstore
+                .transaction_status_index_cf
+                .put(
+                    1,
+                    &TransactionStatusIndexMeta {
+                        max_slot: 7,
+                        frozen: false,
+                    },
+                )
+                .unwrap();
+            blockstore.update_highest_primary_index_slot().unwrap();
+            assert_eq!(blockstore.highest_primary_index_slot(), Some(7));
+
+            // The tracked slot never moves backwards
+            blockstore.set_highest_primary_index_slot(3);
+            assert_eq!(blockstore.highest_primary_index_slot(), Some(7));
+            blockstore.set_highest_primary_index_slot(9);
+            assert_eq!(blockstore.highest_primary_index_slot(), Some(9));
+        }
+
+        // Reopening recomputes the value from the index column
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert_eq!(blockstore.highest_primary_index_slot(), Some(7));
+    }
+
+    #[test]
+    fn test_open_corrupt_transaction_status_index() {
+        let ledger_path = TempDir::new().unwrap();
+        drop(Blockstore::open(ledger_path.path()).unwrap());
+
+        let db_path = ledger_path.path().join(BLOCKSTORE_DIRECTORY_ROCKS_LEVEL);
+        {
+            let cf_names = DB::list_cf(&Options::default(), &db_path).unwrap();
+            let db = DB::open_cf(&Options::default(), &db_path, cf_names).unwrap();
+            let cf = db.cf_handle(cf::TransactionStatusIndex::NAME).unwrap();
+            let key = <cf::TransactionStatusIndex as Column>::key(&2);
+            db.put_cf(cf, key, [0xff; 64]).unwrap();
+        }
+
+        // A corrupt value fails the open instead of panicking
+        assert!(matches!(
+            Blockstore::open(ledger_path.path()),
+            Err(BlockstoreError::Serialize(_))
+        ));
+    }
+
+    #[test]
+    fn test_write_and_read_transaction_status() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let signature = Signature::from([1; 64]);
+        let status = TransactionStatusMeta {
+            err: None,
+            fee: 5_000,
+            pre_balances: vec![10_000, 0],
+            post_balances: vec![4_000, 1_000],
+        };
+        assert_eq!(blockstore.read_transaction_status(signature).unwrap(), None);
+
+        blockstore
+            .write_transaction_status(4, signature, &status)
+            .unwrap();
+        assert_eq!(
+            blockstore.read_transaction_status(signature).unwrap(),
+            Some((4, status.clone()))
+        );
+        // An unknown signature does not match the next one stored in the column
+        assert_eq!(
+            blockstore
+                .read_transaction_status(Signature::from([0; 64]))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            blockstore.transaction_status_index_cf.get(0).unwrap(),
+            Some(TransactionStatusIndexMeta {
+                max_slot: 4,
+                frozen: false,
+            })
+        );
+        assert_eq!(blockstore.highest_primary_index_slot(), Some(4));
+
+        // Once the first index is frozen, writes go to the second one
+        blockstore
+            .transaction_status_index_cf
+            .put(
+                0,
+                &TransactionStatusIndexMeta {
+                    max_slot: 4,
+                    frozen: true,
+                },
+            )
+            .unwrap();
+        let failed_status = TransactionStatusMeta {
+            err: Some("insufficient funds".to_string()),
+            ..status
+        };
+        let other_signature = Signature::from([2; 64]);
+        blockstore
+            .write_transaction_status(6, other_signature, &failed_status)
+            .unwrap();
+        assert_eq!(
+            blockstore.read_transaction_status(other_signature).unwrap(),
+            Some((6, failed_status))
+        );
+        assert_eq!(
+            blockstore.transaction_status_index_cf.get(1).unwrap(),
+            Some(TransactionStatusIndexMeta {
+                max_slot: 6,
+                frozen: false,
+            })
+        );
+        assert_eq!(blockstore.highest_primary_index_slot(), Some(6));
+    }
+
+    #[test]
+    fn test_get_confirmed_signatures_for_address() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let address0 = Pubkey::new_from_array([1; 32]);
+        let address1 = Pubkey::new_from_array([2; 32]);
+        let signatures: Vec<_> = (1..=4).map(|i| Signature::from([i; 64])).collect();
+        for (slot, signature) in (10..).zip(&signatures) {
+            blockstore
+                .write_address_signature(address0, slot, *signature, slot % 2 == 0)
+                .unwrap();
+        }
+        blockstore
+            .write_address_signature(address1, 11, signatures[1], true)
+            .unwrap();
+        blockstore
+            .write_address_signature(address1, 20, signatures[3], false)
+            .unwrap();
+
+        let address_signature = |slot, signature, writeable| AddressSignature {
+            slot,
+            signature,
+            writeable,
+        };
+        assert_eq!(
+            blockstore
+                .get_confirmed_signatures_for_address(address0, None, None, 3)
+                .unwrap(),
+            vec![
+                address_signature(13, signatures[3], false),
+                address_signature(12, signatures[2], true),
+                address_signature(11, signatures[1], false),
+            ]
+        );
+        assert_eq!(
+            blockstore
+                .get_confirmed_signatures_for_address(address1, None, None, 1)
+                .unwrap(),
+            vec![address_signature(20, signatures[3], false)]
+        );
+        assert_eq!(
+            blockstore
+                .get_confirmed_signatures_for_address(address1, None, None, 10)
+                .unwrap(),
+            vec![
+                address_signature(20, signatures[3], false),
+                address_signature(11, signatures[1], true),
+            ]
+        );
+
+        // before and until are both exclusive
+        assert_eq!(
+            blockstore
+                .get_confirmed_signatures_for_address(
+                    address0,
+                    Some(signatures[3]),
+                    Some(signatures[0]),
+                    10
+                )
+                .unwrap(),
+            vec![
+                address_signature(12, signatures[2], true),
+                address_signature(11, signatures[1], false),
+            ]
+        );
+        // signatures[0] never referenced address1
+        assert!(blockstore
+            .get_confirmed_signatures_for_address(address1, Some(signatures[0]), None, 10)
+            .unwrap()
+            .is_empty());
+        assert!(blockstore
+            .get_confirmed_signatures_for_address(Pubkey::default(), None, None, 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_get_recent_perf_samples() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert!(blockstore.get_recent_perf_samples(10).unwrap().is_empty());
+
+        let perf_samples: Vec<_> = (0..5)
+            .map(|i| {
+                let perf_sample = PerfSample {
+                    num_transactions: 1_000 * i,
+                    num_slots: 150,
+                    sample_period_secs: 60,
+                };
+                (i * 150, perf_sample)
+            })
+            .collect();
+        for (slot, perf_sample) in &perf_samples {
+            blockstore.write_perf_sample(*slot, perf_sample).unwrap();
+        }
+
+        let expected: Vec<_> = perf_samples.iter().rev().take(3).cloned().collect();
+        assert_eq!(blockstore.get_recent_perf_samples(3).unwrap(), expected);
+
+        let expected: Vec<_> = perf_samples.into_iter().rev().collect();
+        assert_eq!(blockstore.get_recent_perf_samples(10).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_get_latest_optimistic_slots() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert!(blockstore
+            .get_latest_optimistic_slots(5)
+            .unwrap()
+            .is_empty());
+
+        let optimistic_slots: Vec<_> = [3, 1, 7, 5]
+            .into_iter()
+            .map(|slot| {
+                (
+                    slot,
+                    Hash::new_from_array([slot as u8; 32]),
+                    slot as i64 * 400,
+                )
+            })
+            .collect();
+        for (slot, hash, timestamp) in &optimistic_slots {
+            blockstore
+                .insert_optimistic_slot(*slot, hash, *timestamp)
+                .unwrap();
+        }
+
+        let latest = blockstore.get_latest_optimistic_slots(2).unwrap();
+        assert_eq!(
+            latest,
+            vec![
+                (7, Hash::new_from_array([7; 32]), 2_800),
+                (5, Hash::new_from_array([5; 32]), 2_000),
+            ]
+        );
+        let latest = blockstore.get_latest_optimistic_slots(10).unwrap();
+        assert_eq!(
+            latest.iter().map(|(slot, _, _)| *slot).collect::<Vec<_>>(),
+            vec![7, 5, 3, 1]
+        );
+    }
+
+    #[test]
+    fn test_block_height() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert_eq!(blockstore.max_block_height().unwrap(), None);
+
+        blockstore.set_block_height(2, 1).unwrap();
+        blockstore.set_block_height(5, 3).unwrap();
+        blockstore.set_block_height(4, 2).unwrap();
+
+        assert_eq!(blockstore.get_block_height(2).unwrap(), Some(1));
+        assert_eq!(blockstore.get_block_height(5).unwrap(), Some(3));
+        // Slot 3 was skipped
+        assert_eq!(blockstore.get_block_height(3).unwrap(), None);
+        assert_eq!(blockstore.max_block_height().unwrap(), Some(3));
+    }
+
+    #[test]
+    fn test_insert_bank_hash() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let slot = 3;
+        let frozen_hash = Hash::new_from_array([3; 32]);
+
+        // Missing slot
+        assert_eq!(blockstore.get_bank_hash(slot).unwrap(), None);
+        assert!(!blockstore.is_duplicate_confirmed(slot).unwrap());
+
+        blockstore
+            .insert_bank_hash(slot, frozen_hash, false)
+            .unwrap();
+        assert_eq!(blockstore.get_bank_hash(slot).unwrap(), Some(frozen_hash));
+        assert!(!blockstore.is_duplicate_confirmed(slot).unwrap());
+
+        // Upgrade the same hash to duplicate confirmed
+        blockstore
+            .insert_bank_hash(slot, frozen_hash, true)
+            .unwrap();
+        assert_eq!(blockstore.get_bank_hash(slot).unwrap(), Some(frozen_hash));
+        assert!(blockstore.is_duplicate_confirmed(slot).unwrap());
+
+        // A duplicate confirmed hash is not downgraded
+        blockstore
+            .insert_bank_hash(slot, frozen_hash, false)
+            .unwrap();
+        assert!(blockstore.is_duplicate_confirmed(slot).unwrap());
+
+        // A different hash replaces it
+        let other_hash = Hash::new_from_array([4; 32]);
+        blockstore
+            .insert_bank_hash(slot, other_hash, false)
+            .unwrap();
+        assert_eq!(blockstore.get_bank_hash(slot).unwrap(), Some(other_hash));
+        assert!(!blockstore.is_duplicate_confirmed(slot).unwrap());
+    }
+
+    #[test]
+    fn test_corrupt_bank_hash() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        blockstore.bank_hash_cf.put_bytes(3, &[0xff; 3]).unwrap();
+
+        // Corruption is reported instead of panicking
+        assert!(blockstore.get_bank_hash(3).is_err());
+        assert!(blockstore.is_duplicate_confirmed(3).is_err());
+        assert!(blockstore
+            .insert_bank_hash(3, Hash::new_unique(), false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_set_dead_slot() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        for slot in [2, 5, 9] {
+            blockstore.set_dead_slot(slot).unwrap();
+        }
+
+        assert!(blockstore.is_dead(5).unwrap());
+        assert!(!blockstore.is_dead(4).unwrap());
+        {
+            let mut stats = blockstore.slots_stats.stats.lock().unwrap();
+            assert!(stats.get(&5).unwrap().is_dead());
+            assert!(stats.get(&4).is_none());
+        }
+
+        let dead_slots: Vec<_> = blockstore
+            .dead_slots_iterator(3, IteratorDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(dead_slots, [5, 9]);
+    }
+
+    #[test]
+    fn test_open_with_slots_stats_capacity() {
+        let ledger_path = TempDir::new().unwrap();
+        let capacity = NonZeroUsize::new(2).unwrap();
+        let blockstore = Blockstore::open_with_options(
+            ledger_path.path(),
+            BlockstoreOptions {
+                slots_stats_capacity: Some(capacity),
+                ..BlockstoreOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(blockstore.slots_stats.stats.lock().unwrap().cap(), capacity);
+    }
+
+    #[test]
+    fn test_write_and_get_rewards() {
+        let ledger_path = TempDir::new().unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let rewards = vec![
+            Reward {
+                pubkey: Pubkey::new_unique().to_string(),
+                lamports: 450_000,
+                post_balance: 1_450_000,
+                reward_type: Some(RewardType::Staking),
+                commission: Some(10),
+            },
+            Reward {
+                pubkey: Pubkey::new_unique().to_string(),
+                lamports: 50_000,
+                post_balance: 2_050_000,
+                reward_type: Some(RewardType::Voting),
+                commission: Some(10),
+            },
+        ];
+
+        blockstore.write_rewards(8, &rewards).unwrap();
+        assert_eq!(blockstore.get_rewards(8).unwrap(), Some(rewards));
+        assert_eq!(blockstore.get_rewards(9).unwrap(), None);
+    }
+
+    #[test]
+    fn test_open_secondary() {
+        let ledger_path = TempDir::new().unwrap();
+        create_new_ledger(
+            ledger_path.path(),
+            &GenesisConfig::default(),
+            u64::MAX,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            blockstore.roots_cf.put(0, &true).unwrap();
+            blockstore.set_block_height(0, 0).unwrap();
+        }
+
+        let blockstore = Blockstore::open_secondary(ledger_path.path()).unwrap();
+        assert!(!blockstore.is_primary_access());
+        let roots: Vec<_> = blockstore
+            .rooted_slot_iterator(0, IteratorDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(roots, [0]);
+        assert_eq!(blockstore.get_block_height(0).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_create_new_ledger_dry_run() {
+        let ledger_path = TempDir::new().unwrap();
+        let genesis_config = GenesisConfig {
+            ticks_per_slot: 8,
+            ..GenesisConfig::default()
+        };
+
+        let dry_run_hash = create_new_ledger_dry_run(
+            ledger_path.path(),
+            &genesis_config,
+            u64::MAX,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_dir(ledger_path.path()).unwrap().count(), 0);
+
+        let hash = create_new_ledger(
+            ledger_path.path(),
+            &genesis_config,
+            u64::MAX,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(dry_run_hash, hash);
+    }
+
+    #[test]
+    fn test_create_new_ledger_invalid_poh_config() {
+        let ledger_path = TempDir::new().unwrap();
+        let create = |genesis_config: &GenesisConfig| {
+            create_new_ledger(
+                ledger_path.path(),
+                genesis_config,
+                u64::MAX,
+                LedgerColumnOptions::default(),
+            )
+        };
+
+        let mut genesis_config = GenesisConfig {
+            ticks_per_slot: 0,
+            ..GenesisConfig::default()
+        };
+        assert!(matches!(
+            create(&genesis_config),
+            Err(BlockstoreError::InvalidTicksPerSlot(0))
+        ));
+        // Nothing is written for a rejected config
+        assert_eq!(std::fs::read_dir(ledger_path.path()).unwrap().count(), 0);
+
+        genesis_config.ticks_per_slot = 1;
+        genesis_config.poh_config.hashes_per_tick = Some(1);
+        assert!(matches!(
+            create(&genesis_config),
+            Err(BlockstoreError::InvalidHashesPerTick(1))
+        ));
+
+        for hashes_per_tick in [None, Some(0), Some(2)] {
+            genesis_config.poh_config.hashes_per_tick = hashes_per_tick;
+            create(&genesis_config).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verify_genesis_slot() {
+        let ledger_path = TempDir::new().unwrap();
+        let genesis_config = GenesisConfig::default();
+        create_new_ledger(
+            ledger_path.path(),
+            &genesis_config,
+            u64::MAX,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            verify_genesis_slot(ledger_path.path(), u64::MAX).unwrap(),
+            genesis_config.hash()
+        );
+
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            blockstore.roots_cf.delete(0).unwrap();
+        }
+        assert!(matches!(
+            verify_genesis_slot(ledger_path.path(), u64::MAX),
+            Err(BlockstoreError::SlotNotRooted)
+        ));
+    }
+
+    #[test]
+    fn test_verify_genesis_slot_not_full() {
+        let ledger_path = TempDir::new().unwrap();
+        create_new_ledger(
+            ledger_path.path(),
+            &GenesisConfig::default(),
+            u64::MAX,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            let mut slot_meta = blockstore.meta(0).unwrap().unwrap();
+            slot_meta.consumed -= 1;
+            blockstore.meta_cf.put(0, &slot_meta).unwrap();
+        }
+        assert!(matches!(
+            verify_genesis_slot(ledger_path.path(), u64::MAX),
+            Err(BlockstoreError::SlotNotFull(0))
+        ));
+
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            blockstore.meta_cf.delete(0).unwrap();
+        }
+        assert!(matches!(
+            verify_genesis_slot(ledger_path.path(), u64::MAX),
+            Err(BlockstoreError::SlotUnavailable)
+        ));
+    }
+
+    #[test]
+    fn test_verify_genesis_slot_broken_tick_chain() {
+        let ledger_path = TempDir::new().unwrap();
+        let mut genesis_config = GenesisConfig {
+            ticks_per_slot: 8,
+            ..GenesisConfig::default()
+        };
+        genesis_config.poh_config.hashes_per_tick = Some(4);
+        create_new_ledger(
+            ledger_path.path(),
+            &genesis_config,
+            u64::MAX,
+            LedgerColumnOptions::default(),
+        )
+        .unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            let mut ticks = blockstore.get_genesis_ticks().unwrap();
+            assert_eq!(ticks.len(), 8);
+            assert!(ticks_chain_from(&genesis_config.hash(), &ticks));
+
+            // A tick that doesn't follow from the previous one
+            ticks[3] = Entry::new(&Hash::new_unique(), 4, vec![]);
+            blockstore.genesis_ticks_cf.put(0, &ticks).unwrap();
+        }
+        assert!(matches!(
+            verify_genesis_slot(ledger_path.path(), u64::MAX),
+            Err(BlockstoreError::InvalidGenesisTicks(hash)) if hash == genesis_config.hash()
+        ));
+    }
+
+    #[test]
+    fn test_verify_genesis_slot_missing_genesis() {
+        let ledger_path = TempDir::new().unwrap();
+        assert!(matches!(
+            verify_genesis_slot(ledger_path.path(), u64::MAX),
+            Err(BlockstoreError::OpenGenesisConfig(_))
+        ));
     }
 }
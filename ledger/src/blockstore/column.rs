@@ -5,6 +5,7 @@ use {
         blockstore_meta::{self},
     },
     bincode::Options as BincodeOptions,
+    blockchain_entry::entry::Entry,
     serde::{de::DeserializeOwned, Serialize},
     solana_clock::{Slot, UnixTimestamp},
     solana_pubkey::{Pubkey, PUBKEY_BYTES},
@@ -132,7 +133,7 @@ pub mod columns {
     /// The transaction status column
     ///
     /// * index type: `(`[`Signature`]`, `[`Slot`])`
-    /// * value type: [`generated::TransactionStatusMeta`]
+    /// * value type: [`blockstore_meta::TransactionStatusMeta`]
     pub struct TransactionStatus;
 
     #[derive(Debug)]
@@ -160,7 +161,7 @@ pub mod columns {
     /// The rewards column
     ///
     /// * index type: `u64` (see [`SlotColumn`])
-    /// * value type: [`generated::Rewards`]
+    /// * value type: [`Rewards`](blockchain_transaction_status_client_types::Rewards)
     pub struct Rewards;
 
     #[derive(Debug)]
@@ -203,6 +204,16 @@ pub mod columns {
     /// * index type: `crate::shred::ErasureSetId` `(Slot, fec_set_index: u32)`
     /// * value type: [`blockstore_meta::MerkleRootMeta`]`
     pub struct MerkleRootMeta;
+
+    #[derive(Debug)]
+    /// The genesis ticks column
+    ///
+    /// Holds the ticks that fill slot 0 of a new ledger. The ledger has no
+    /// shredder yet, so the entries are kept here rather than as data shreds.
+    ///
+    /// * index type: `u64` (see [`SlotColumn`])
+    /// * value type: `Vec<`[`blockchain_entry::entry::Entry`]`>`
+    pub struct GenesisTicks;
 }
 
 macro_rules! convert_column_index_to_key_bytes {
@@ -307,6 +318,17 @@ impl TypedColumn for columns::TransactionMemos {
 
 impl TypedColumn for columns::TransactionStatusIndex {
     type Type = blockstore_meta::TransactionStatusIndexMeta;
+
+    fn deserialize(data: &[u8]) -> Result<Self::Type> {
+        // The value has a fixed serialized size, so bound the allocation of a
+        // corrupt one to that size
+        let config = bincode::DefaultOptions::new()
+            // `bincode::serialize` uses fixint encoding by default, so we need to use the same here
+            .with_fixint_encoding()
+            .with_limit(Self::Type::SERIALIZED_SIZE)
+            .reject_trailing_bytes();
+        Ok(config.deserialize::<Self::Type>(data)?)
+    }
 }
 
 impl<T: SlotColumn> Column for T {
@@ -362,9 +384,9 @@ impl Column for columns::TransactionStatus {
 impl ColumnName for columns::TransactionStatus {
     const NAME: &'static str = "transaction_status";
 }
-// impl ProtobufColumn for columns::TransactionStatus {
-//     type Type = generated::TransactionStatusMeta;
-// }
+impl TypedColumn for columns::TransactionStatus {
+    type Type = blockstore_meta::TransactionStatusMeta;
+}
 
 impl ColumnIndexDeprecation for columns::TransactionStatus {
     const CURRENT_INDEX_LEN: usize = 72;
@@ -572,9 +594,9 @@ impl SlotColumn for columns::Rewards {}
 impl ColumnName for columns::Rewards {
     const NAME: &'static str = "rewards";
 }
-// impl ProtobufColumn for columns::Rewards {
-//     type Type = generated::Rewards;
-// }
+impl TypedColumn for columns::Rewards {
+    type Type = blockchain_transaction_status_client_types::Rewards;
+}
 
 impl SlotColumn for columns::Blocktime {}
 impl ColumnName for columns::Blocktime {
@@ -588,6 +610,9 @@ impl SlotColumn for columns::PerfSamples {}
 impl ColumnName for columns::PerfSamples {
     const NAME: &'static str = "perf_samples";
 }
+impl TypedColumn for columns::PerfSamples {
+    type Type = blockstore_meta::PerfSample;
+}
 
 impl SlotColumn for columns::BlockHeight {}
 impl ColumnName for columns::BlockHeight {
@@ -597,6 +622,14 @@ impl TypedColumn for columns::BlockHeight {
     type Type = u64;
 }
 
+impl SlotColumn for columns::GenesisTicks {}
+impl ColumnName for columns::GenesisTicks {
+    const NAME: &'static str = "genesis_ticks";
+}
+impl TypedColumn for columns::GenesisTicks {
+    type Type = Vec<Entry>;
+}
+
 impl Column for columns::ShredCode {
     type Index = (Slot, /*shred index:*/ u64);
     type Key = <columns::ShredData as Column>::Key;
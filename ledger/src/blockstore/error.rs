@@ -1,6 +1,8 @@
 //! The error that can be produced from Blockstore operations.
 
-use blockchain_accounts_db::hardened_unpack::UnpackError;
+use blockchain_accounts_db::hardened_unpack::{OpenGenesisConfigError, UnpackError};
+use solana_hash::Hash;
+use std::path::PathBuf;
 use {solana_clock::Slot, thiserror::Error};
 
 #[derive(Error, Debug)]
@@ -57,5 +59,27 @@ pub enum BlockstoreError {
     LegacyShred(Slot, u64),
     #[error("unable to read merkle root slot {0}, index {1}")]
     MissingMerkleRoot(Slot, u64),
+    #[error("blockstore is already open in primary mode by another instance: {0}")]
+    AlreadyInUse(PathBuf),
+    #[error("{column} column, key {key}: {source}")]
+    ColumnValue {
+        column: &'static str,
+        key: String,
+        source: Box<BlockstoreError>,
+    },
+    #[error("operation requires primary access to the blockstore")]
+    PrimaryAccessRequired,
+    #[error("invalid ticks per slot: {0}, must be at least 1")]
+    InvalidTicksPerSlot(u64),
+    #[error("invalid hashes per tick: {0}, must be 0 or greater than 1")]
+    InvalidHashesPerTick(u64),
+    #[error("data shred index out of range slot {0}, index {1}")]
+    InvalidDataShredIndex(Slot, u64),
+    #[error("slot {0} is not full")]
+    SlotNotFull(Slot),
+    #[error("slot 0 does not chain from the genesis hash {0}")]
+    InvalidGenesisTicks(Hash),
+    #[error("open genesis config error: {0}")]
+    OpenGenesisConfig(#[from] OpenGenesisConfigError),
 }
 pub type Result<T> = std::result::Result<T, BlockstoreError>;
@@ -1,16 +1,17 @@
 use crate::blockstore::column::{
     columns, Column, ColumnName, TypedColumn, DEPRECATED_PROGRAM_COSTS_COLUMN_NAME,
 };
-use crate::blockstore::error::Result;
+use crate::blockstore::error::{BlockstoreError, Result};
 use crate::blockstore_metrics::{maybe_enable_rocksdb_perf, PerfSamplingStatus};
 use crate::blockstore_options::{AccessType, BlockstoreOptions, LedgerColumnOptions};
 use log::{info, warn};
+use rocksdb::checkpoint::Checkpoint;
 use rocksdb::compaction_filter::CompactionFilter;
 use rocksdb::compaction_filter_factory::{CompactionFilterContext, CompactionFilterFactory};
 pub use rocksdb::Direction as IteratorDirection;
 use rocksdb::{
     ColumnFamily, ColumnFamilyDescriptor, CompactionDecision, DBCompressionType, DBIterator,
-    DBPinnableSlice, IteratorMode as RocksIteratorMode, Options, DB,
+    DBPinnableSlice, FlushOptions, IteratorMode as RocksIteratorMode, Options, DB,
 };
 use solana_clock::Slot;
 use std::collections::HashSet;
@@ -24,14 +25,6 @@ use std::sync::Arc;
 
 const MAX_WRITE_BUFFER_SIZE: u64 = 256 * 1024 * 1024; // 256MB
 
-// SST files older than this value will be picked up for compaction. This value
-// was chosen to be one day to strike a balance between storage getting
-// reclaimed in a timely manner and the additional I/O that compaction incurs.
-// For more details on this property, see
-// https://github.com/facebook/rocksdb/blob/749b179c041347d150fa6721992ae8398b7d2b39/
-//   include/rocksdb/advanced_options.h#L908C30-L908C30
-const PERIODIC_COMPACTION_SECONDS: u64 = 60 * 60 * 24;
-
 #[derive(Default, Clone, Debug)]
 struct OldestSlot {
     slot: Arc<AtomicU64>,
@@ -102,10 +95,40 @@ where
         self.backend.cf_handle(C::NAME)
     }
 
+    pub fn get_bytes(&self, index: C::Index) -> Result<Option<Vec<u8>>> {
+        let key = <C as Column>::key(&index);
+        Ok(self
+            .backend
+            .get_pinned_cf(self.handle(), key)?
+            .map(|pinnable_slice| pinnable_slice.to_vec()))
+    }
+
+    #[cfg(test)]
+    pub fn put_bytes(&self, index: C::Index, value: &[u8]) -> Result<()> {
+        let key = <C as Column>::key(&index);
+        self.backend.put_cf(self.handle(), key, value)
+    }
+
     pub fn delete(&self, index: C::Index) -> Result<()> {
         let key = <C as Column>::key(&index);
         self.backend.delete_cf(self.handle(), key)
     }
+
+    /// Manually compacts the keys of this column that belong to slots in the
+    /// inclusive range `[from, to]`.
+    ///
+    /// Only meaningful for columns whose keys are prefixed by the slot.
+    pub fn compact_range(&self, from: Slot, to: Slot) {
+        let from = <C as Column>::key(&C::as_index(from));
+        let to = <C as Column>::key(&C::as_index(to.saturating_add(1)));
+        self.backend
+            .compact_range_cf(C::NAME, Some(from.as_ref()), Some(to.as_ref()));
+    }
+
+    #[cfg(test)]
+    pub fn get_int_property(&self, name: &'static CStr) -> Result<i64> {
+        self.backend.get_int_property_cf(self.handle(), name)
+    }
 }
 
 impl<C> LedgerColumn<C>
@@ -117,30 +140,109 @@ where
         self.get_raw(key)
     }
 
-    pub fn put(&self, index: C::Index, value: &C::Type) -> Result<()> {
-        let serialized_value = C::serialize(value)?;
+    /// Like [`LedgerColumn::iter`], but deserializes the values. A value that
+    /// can't be deserialized is yielded as an error, and the iteration can go
+    /// on past it.
+    pub fn iter_deserialized(
+        &self,
+        iterator_mode: IteratorMode<C::Index>,
+    ) -> Result<impl Iterator<Item = Result<(C::Index, C::Type)>> + '_> {
+        Ok(self.iter(iterator_mode)?.map(|(index, value)| {
+            let value = C::deserialize(&value)
+                .map_err(|err| column_value_error::<C>(<C as Column>::key(&index).as_ref(), err))?;
+            Ok((index, value))
+        }))
+    }
 
+    pub fn put(&self, index: C::Index, value: &C::Type) -> Result<()> {
         let key = <C as Column>::key(&index);
+        let serialized_value =
+            C::serialize(value).map_err(|err| column_value_error::<C>(key.as_ref(), err))?;
+
         let result = self.backend.put_cf(self.handle(), key, &serialized_value);
 
         result
     }
 
     pub fn get_raw<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<C::Type>> {
+        let key = key.as_ref();
         let mut result = Ok(None);
         if let Some(pinnable_slice) = self.backend.get_pinned_cf(self.handle(), key)? {
-            let value = C::deserialize(pinnable_slice.as_ref())?;
+            let value = C::deserialize(pinnable_slice.as_ref())
+                .map_err(|err| column_value_error::<C>(key, err))?;
             result = Ok(Some(value))
         }
         result
     }
 }
 
+/// Whether `err` is rocksdb failing to take the lock of a database that is
+/// already open in primary mode.
+fn is_lock_error(err: &rocksdb::Error) -> bool {
+    err.kind() == rocksdb::ErrorKind::IOError && is_lock_error_message(err.as_ref())
+}
+
+/// Whether `message` is one of the errors rocksdb reports when the LOCK file
+/// of the database is held, by another process or by this one. The path in the
+/// message is not looked at, as it can contain "lock" on its own, e.g. in
+/// "blockstore".
+fn is_lock_error_message(message: &str) -> bool {
+    message.contains("While lock file: ") || message.contains("lock hold by current process")
+}
+
+/// Reports a failure to take the lock of the database at `path` as
+/// [`BlockstoreError::AlreadyInUse`].
+fn lock_error(path: &Path, err: rocksdb::Error) -> BlockstoreError {
+    if is_lock_error(&err) {
+        warn!("Unable to lock {path:?}: {err}");
+        BlockstoreError::AlreadyInUse(path.to_path_buf())
+    } else {
+        err.into()
+    }
+}
+
+// Number of leading key bytes included in column value errors; enough to
+// cover the slot and most of any following index.
+const ERROR_KEY_PREFIX_LEN: usize = 16;
+
+// Attaches the column name and the hex encoded key prefix to a failure to
+// (de)serialize a value of column `C`.
+fn column_value_error<C: ColumnName>(key: &[u8], err: BlockstoreError) -> BlockstoreError {
+    let mut key_prefix: String = key
+        .iter()
+        .take(ERROR_KEY_PREFIX_LEN)
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    if key.len() > ERROR_KEY_PREFIX_LEN {
+        key_prefix.push_str("..");
+    }
+    BlockstoreError::ColumnValue {
+        column: C::NAME,
+        key: key_prefix,
+        source: Box::new(err),
+    }
+}
+
+// Whether `key` of column `cf_name` is part of the genesis slot that
+// create_new_ledger() always roots and fills with ticks.
+fn is_genesis_slot_key(cf_name: &str, key: &[u8]) -> bool {
+    let slot = match cf_name {
+        columns::Root::NAME => columns::Root::slot(columns::Root::index(key)),
+        columns::SlotMeta::NAME => columns::SlotMeta::slot(columns::SlotMeta::index(key)),
+        columns::GenesisTicks::NAME => {
+            columns::GenesisTicks::slot(columns::GenesisTicks::index(key))
+        }
+        _ => return false,
+    };
+    slot == 0
+}
+
 #[derive(Debug)]
 pub(crate) struct Rocks {
     db: DB,
     path: PathBuf,
     access_type: AccessType,
+    disable_auto_compactions: bool,
     oldest_slot: OldestSlot,
     column_options: Arc<LedgerColumnOptions>,
     write_batch_perf_status: PerfSamplingStatus,
@@ -164,7 +266,8 @@ impl Rocks {
         // Open the database
         let mut db = match options.access_type {
             AccessType::Primary | AccessType::PrimaryForMaintenance => {
-                DB::open_cf_descriptors(&db_options, &path, cf_descriptors)?
+                DB::open_cf_descriptors(&db_options, &path, cf_descriptors)
+                    .map_err(|err| lock_error(&path, err))?
             }
             AccessType::Secondary => {
                 let secondary_path = path.join("solana-secondary");
@@ -182,14 +285,21 @@ impl Rocks {
             }
         };
 
-        // Delete the now unused program_costs column if it is present
-        if db.cf_handle(DEPRECATED_PROGRAM_COSTS_COLUMN_NAME).is_some() {
+        // Delete the now unused program_costs column if it is present; a
+        // secondary instance cannot modify the database so it leaves it be
+        if options.access_type != AccessType::Secondary
+            && db.cf_handle(DEPRECATED_PROGRAM_COSTS_COLUMN_NAME).is_some()
+        {
             db.drop_cf(DEPRECATED_PROGRAM_COSTS_COLUMN_NAME)?;
         }
 
         let rocks = Rocks {
             db,
             path,
+            disable_auto_compactions: should_disable_auto_compactions(
+                &options.access_type,
+                options.enable_auto_compactions_for_maintenance,
+            ),
             access_type: options.access_type,
             oldest_slot,
             column_options,
@@ -206,6 +316,8 @@ impl Rocks {
     /// In order to open a RocksDB database with Primary access, all columns must be opened. So,
     /// in addition to creating descriptors for all the expected columns, also create
     /// descriptors for columns that were discovered but are otherwise unknown to the software.
+    /// Secondary access does the same so that every column present on disk is opened regardless
+    /// of the access type.
     ///
     /// One case where columns could be unknown is if a RocksDB database is modified with a newer
     /// software version that adds a new column, and then also opened with an older version that
@@ -235,17 +347,9 @@ impl Rocks {
             new_cf_descriptor::<columns::BlockHeight>(options, oldest_slot),
             new_cf_descriptor::<columns::OptimisticSlots>(options, oldest_slot),
             new_cf_descriptor::<columns::MerkleRootMeta>(options, oldest_slot),
+            new_cf_descriptor::<columns::GenesisTicks>(options, oldest_slot),
         ];
 
-        // If the access type is Secondary, we don't need to open all of the
-        // columns so we can just return immediately.
-        match options.access_type {
-            AccessType::Secondary => {
-                return cf_descriptors;
-            }
-            AccessType::Primary | AccessType::PrimaryForMaintenance => {}
-        }
-
         // Attempt to detect the column families that are present. It is not a
         // fatal error if we cannot, for example, if the Blockstore is brand
         // new and will be created by the call to Rocks::open().
@@ -283,7 +387,7 @@ impl Rocks {
         cf_descriptors
     }
 
-    const fn columns() -> [&'static str; 19] {
+    const fn columns() -> [&'static str; 20] {
         [
             columns::ErasureMeta::NAME,
             columns::DeadSlots::NAME,
@@ -304,6 +408,7 @@ impl Rocks {
             columns::BlockHeight::NAME,
             columns::OptimisticSlots::NAME,
             columns::MerkleRootMeta::NAME,
+            columns::GenesisTicks::NAME,
         ]
     }
 
@@ -321,7 +426,7 @@ impl Rocks {
     // Configure compaction on a per-column basis
     fn configure_compaction(&self) {
         // If compactions are disabled altogether, no need to tune values
-        if should_disable_auto_compactions(&self.access_type) {
+        if self.disable_auto_compactions {
             info!(
                 "Rocks's automatic compactions are disabled due to {:?} access",
                 self.access_type
@@ -337,7 +442,7 @@ impl Rocks {
         // opposed to manual compaction requests on a range.
         // - Periodic compaction operates on individual files once the file
         //   has reached a certain (configurable) age. See comments at
-        //   PERIODIC_COMPACTION_SECONDS for some more deatil.
+        //   DEFAULT_PERIODIC_COMPACTION_SECONDS for some more deatil.
         // - Manual compaction operates on a range and could end up propagating
         //   through several files and/or levels of the db.
         //
@@ -350,14 +455,14 @@ impl Rocks {
         // stall in addition to negatively impacting other parts of the system.
         // Thus, the choice to use periodic compactions is fairly easy.
         for cf_name in Self::columns() {
-            if should_enable_cf_compaction(cf_name) {
+            if should_enable_cf_compaction(cf_name, &self.column_options) {
                 let cf_handle = self.cf_handle(cf_name);
                 self.db
                     .set_options_cf(
                         &cf_handle,
                         &[(
                             "periodic_compaction_seconds",
-                            &PERIODIC_COMPACTION_SECONDS.to_string(),
+                            &self.column_options.periodic_compaction_seconds.to_string(),
                         )],
                     )
                     .unwrap();
@@ -379,12 +484,74 @@ impl Rocks {
         }
     }
 
+    /// Fails with [`BlockstoreError::AlreadyInUse`] if the database at `path`
+    /// is held open in primary mode, by this or another process: rocksdb takes
+    /// the database lock before deleting anything.
     pub(crate) fn destroy(path: &Path) -> Result<()> {
-        DB::destroy(&Options::default(), path)?;
+        DB::destroy(&Options::default(), path).map_err(|err| lock_error(path, err))
+    }
 
+    /// Runs a manual compaction over the keys in `[start, end]` of the column
+    /// named `cf_name`; `None` leaves that side of the range unbounded.
+    pub(crate) fn compact_range_cf(&self, cf_name: &str, start: Option<&[u8]>, end: Option<&[u8]>) {
+        self.db
+            .compact_range_cf(self.cf_handle(cf_name), start, end);
+    }
+
+    /// Flushes the memtables of every column to SST files and syncs the WAL,
+    /// so that everything written so far is durable.
+    pub(crate) fn flush(&self) -> Result<()> {
+        let cfs: Vec<_> = Self::columns()
+            .into_iter()
+            .map(|cf_name| self.cf_handle(cf_name))
+            .collect();
+        self.db.flush_cfs_opt(&cfs, &FlushOptions::default())?;
+        self.db.flush_wal(true)?;
         Ok(())
     }
 
+    /// Creates a consistent copy of the database at `path`, which must not
+    /// exist yet. SST files are hard-linked when `path` is on the same
+    /// filesystem.
+    pub(crate) fn create_checkpoint(&self, path: &Path) -> Result<()> {
+        Checkpoint::new(&self.db)?.create_checkpoint(path)?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_int_property_cf(
+        &self,
+        cf: &ColumnFamily,
+        name: &'static CStr,
+    ) -> Result<i64> {
+        let value = self.db.property_int_value_cf(cf, name)?.unwrap_or(0);
+        Ok(value.try_into().unwrap())
+    }
+
+    /// Returns whether none of the columns of the database at `path` contain
+    /// any data.
+    pub(crate) fn is_empty(path: &Path) -> Result<bool> {
+        // The default column is never written to
+        const DEFAULT_COLUMN_NAME: &str = "default";
+        let cf_names = DB::list_cf(&Options::default(), path)?;
+        let db = DB::open_cf_for_read_only(&Options::default(), path, &cf_names, false)?;
+        for cf_name in &cf_names {
+            // Blockstore::open() always initializes the transaction status
+            // index, so its presence does not mean that data was written
+            if cf_name == DEFAULT_COLUMN_NAME || cf_name == columns::TransactionStatusIndex::NAME {
+                continue;
+            }
+            let cf = db.cf_handle(cf_name).expect("column was listed");
+            for item in db.iterator_cf(cf, RocksIteratorMode::Start) {
+                let (key, _) = item?;
+                if !is_genesis_slot_key(cf_name, &key) {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
     pub(crate) fn iterator_cf(
         &self,
         cf: &ColumnFamily,
@@ -429,6 +596,30 @@ pub fn default_num_flush_threads() -> NonZeroUsize {
     NonZeroUsize::new((num_cpus::get() / 4).max(1)).expect("thread count is non-zero")
 }
 
+/// The number of threads to use for rocksdb compaction given the access type.
+///
+/// Automatic compactions are disabled by default for every access type except
+/// Primary, so there is no point in reserving a full threadpool for those modes.
+pub fn default_num_compaction_threads_for_access_type(access_type: &AccessType) -> NonZeroUsize {
+    if should_disable_auto_compactions(access_type, false) {
+        NonZeroUsize::MIN
+    } else {
+        default_num_compaction_threads()
+    }
+}
+
+/// The number of threads to use for rocksdb memtable flushes given the access
+/// type.
+///
+/// Secondary access never writes to the database, so memtables never need to be
+/// flushed; both Primary modes write and keep the default.
+pub fn default_num_flush_threads_for_access_type(access_type: &AccessType) -> NonZeroUsize {
+    match access_type {
+        AccessType::Primary | AccessType::PrimaryForMaintenance => default_num_flush_threads(),
+        AccessType::Secondary => NonZeroUsize::MIN,
+    }
+}
+
 fn new_cf_descriptor<C: 'static + Column + ColumnName>(
     options: &BlockstoreOptions,
     oldest_slot: &OldestSlot,
@@ -459,36 +650,58 @@ fn get_db_options(blockstore_options: &BlockstoreOptions) -> Options {
         options
             .set_max_background_flushes(blockstore_options.num_rocksdb_flush_threads.get() as i32);
     }
-    // Set max total wal size to 4G.
-    options.set_max_total_wal_size(4 * 1024 * 1024 * 1024);
+    options.set_max_total_wal_size(blockstore_options.max_total_wal_size_bytes);
 
-    if should_disable_auto_compactions(&blockstore_options.access_type) {
+    if should_disable_auto_compactions(
+        &blockstore_options.access_type,
+        blockstore_options.enable_auto_compactions_for_maintenance,
+    ) {
         options.set_disable_auto_compactions(true);
     }
 
-    // Limit to (10) 50 MB log files (500 MB total)
+    // Limit to 50 MB log files (500 MB total with the default of 10 files)
     // Logs grow at < 5 MB / hour, so this provides several days of logs
     options.set_max_log_file_size(50 * 1024 * 1024);
-    options.set_keep_log_file_num(10);
+    options.set_keep_log_file_num(blockstore_options.keep_log_file_num);
 
-    // Allow Rocks to open/keep open as many files as it needs for performance;
-    // however, this is also explicitly required for a secondary instance.
-    // See https://github.com/facebook/rocksdb/wiki/Secondary-instance
-    options.set_max_open_files(-1);
+    options.set_max_open_files(max_open_files(blockstore_options));
 
     options
 }
 
+// Returns the limit of open files for rocksdb, where -1 means unlimited.
+fn max_open_files(blockstore_options: &BlockstoreOptions) -> i32 {
+    match (
+        &blockstore_options.access_type,
+        blockstore_options.max_open_files,
+    ) {
+        // Allow Rocks to open/keep open as many files as it needs for performance;
+        // however, this is also explicitly required for a secondary instance.
+        // See https://github.com/facebook/rocksdb/wiki/Secondary-instance
+        (AccessType::Secondary, _) | (_, None) => -1,
+        (_, Some(max_open_files)) => max_open_files,
+    }
+}
+
 // Returns whether automatic compactions should be disabled for the entire
 // database based upon the given access type.
-fn should_disable_auto_compactions(access_type: &AccessType) -> bool {
-    // Leave automatic compactions enabled (do not disable) in Primary mode;
-    // disable in all other modes to prevent accidental cleaning
-    !matches!(access_type, AccessType::Primary)
+fn should_disable_auto_compactions(
+    access_type: &AccessType,
+    enable_auto_compactions_for_maintenance: bool,
+) -> bool {
+    // Leave automatic compactions enabled (do not disable) in Primary mode, and
+    // in PrimaryForMaintenance mode if asked to; disable in all other modes to
+    // prevent accidental cleaning
+    match access_type {
+        AccessType::Primary => false,
+        AccessType::PrimaryForMaintenance => !enable_auto_compactions_for_maintenance,
+        AccessType::Secondary => true,
+    }
 }
 
-// Returns whether compactions should be enabled for the given column (name).
-fn should_enable_cf_compaction(cf_name: &str) -> bool {
+// Returns whether compactions should be enabled for the given column (name),
+// either always or because the column options ask for it.
+fn should_enable_cf_compaction(cf_name: &str, column_options: &LedgerColumnOptions) -> bool {
     // In order to keep the ledger storage footprint within a desired size,
     // LedgerCleanupService removes data in FIFO order by slot.
     //
@@ -510,7 +723,9 @@ fn should_enable_cf_compaction(cf_name: &str) -> bool {
         columns::TransactionStatus::NAME
             | columns::TransactionMemos::NAME
             | columns::AddressSignatures::NAME
-    )
+    ) || column_options
+        .extra_compaction_filtered_columns
+        .contains(cf_name)
 }
 
 fn get_cf_options<C: 'static + Column + ColumnName>(
@@ -518,25 +733,29 @@ fn get_cf_options<C: 'static + Column + ColumnName>(
     oldest_slot: &OldestSlot,
 ) -> Options {
     let mut cf_options = Options::default();
+    let write_buffer_size = get_write_buffer_size(C::NAME, &options.column_options);
     // 256 * 8 = 2GB. 6 of these columns should take at most 12GB of RAM
     cf_options.set_max_write_buffer_number(8);
-    cf_options.set_write_buffer_size(MAX_WRITE_BUFFER_SIZE as usize);
+    cf_options.set_write_buffer_size(write_buffer_size as usize);
     let file_num_compaction_trigger = 4;
     // Recommend that this be around the size of level 0. Level 0 estimated size in stable state is
     // write_buffer_size * min_write_buffer_number_to_merge * level0_file_num_compaction_trigger
     // Source: https://docs.rs/rocksdb/0.6.0/rocksdb/struct.Options.html#method.set_level_zero_file_num_compaction_trigger
-    let total_size_base = MAX_WRITE_BUFFER_SIZE * file_num_compaction_trigger;
+    let total_size_base = write_buffer_size * file_num_compaction_trigger;
     let file_size_base = total_size_base / 10;
     cf_options.set_level_zero_file_num_compaction_trigger(file_num_compaction_trigger as i32);
     cf_options.set_max_bytes_for_level_base(total_size_base);
     cf_options.set_target_file_size_base(file_size_base);
 
-    let disable_auto_compactions = should_disable_auto_compactions(&options.access_type);
+    let disable_auto_compactions = should_disable_auto_compactions(
+        &options.access_type,
+        options.enable_auto_compactions_for_maintenance,
+    );
     if disable_auto_compactions {
         cf_options.set_disable_auto_compactions(true);
     }
 
-    if !disable_auto_compactions && should_enable_cf_compaction(C::NAME) {
+    if !disable_auto_compactions && should_enable_cf_compaction(C::NAME, &options.column_options) {
         cf_options.set_compaction_filter_factory(PurgedSlotFilterFactory::<C> {
             oldest_slot: oldest_slot.clone(),
             name: CString::new(format!("purged_slot_filter_factory({})", C::NAME)).unwrap(),
@@ -549,6 +768,16 @@ fn get_cf_options<C: 'static + Column + ColumnName>(
     cf_options
 }
 
+// Returns the memtable write buffer size for the given column (name), which is
+// MAX_WRITE_BUFFER_SIZE unless overridden in the column options.
+fn get_write_buffer_size(cf_name: &str, column_options: &LedgerColumnOptions) -> u64 {
+    column_options
+        .write_buffer_size_overrides
+        .get(cf_name)
+        .copied()
+        .unwrap_or(MAX_WRITE_BUFFER_SIZE)
+}
+
 /// A CompactionFilter implementation to remove keys older than a given slot.
 struct PurgedSlotFilter<C: Column + ColumnName> {
     /// The oldest slot to keep; any slot < oldest_slot will be removed
@@ -563,14 +792,8 @@ struct PurgedSlotFilter<C: Column + ColumnName> {
 
 impl<C: Column + ColumnName> CompactionFilter for PurgedSlotFilter<C> {
     fn filter(&mut self, _level: u32, key: &[u8], _value: &[u8]) -> CompactionDecision {
-        use rocksdb::CompactionDecision::*;
-
         let slot_in_key = C::slot(C::index(key));
-        if slot_in_key >= self.oldest_slot || (slot_in_key == 0 && !self.clean_slot_0) {
-            Keep
-        } else {
-            Remove
-        }
+        purge_decision(slot_in_key, self.oldest_slot, self.clean_slot_0)
     }
 
     fn name(&self) -> &CStr {
@@ -578,6 +801,19 @@ impl<C: Column + ColumnName> CompactionFilter for PurgedSlotFilter<C> {
     }
 }
 
+/// Decides whether a key of `slot_in_key` survives a compaction: keys of slots
+/// older than `oldest_slot` are removed, except slot 0 keys unless
+/// `clean_slot_0` is set.
+fn purge_decision(slot_in_key: Slot, oldest_slot: Slot, clean_slot_0: bool) -> CompactionDecision {
+    use rocksdb::CompactionDecision::*;
+
+    if slot_in_key >= oldest_slot || (slot_in_key == 0 && !clean_slot_0) {
+        Keep
+    } else {
+        Remove
+    }
+}
+
 struct PurgedSlotFilterFactory<C: Column + ColumnName> {
     oldest_slot: OldestSlot,
     name: CString,
@@ -629,3 +865,345 @@ fn process_cf_options_advanced<C: 'static + Column + ColumnName>(
 fn should_enable_compression<C: 'static + Column + ColumnName>() -> bool {
     C::NAME == columns::TransactionStatus::NAME
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_error() {
+        assert!(is_lock_error_message(
+            "IO error: While lock file: /ledger/rocksdb/LOCK: Resource temporarily unavailable"
+        ));
+        assert!(is_lock_error_message(
+            "IO error: lock hold by current process, acquire time 1 acquiring thread 2: \
+             /ledger/rocksdb/LOCK: No locks available"
+        ));
+
+        // Failing to open the LOCK file, under a path containing "lock", is not
+        // the lock being held
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("blockstore");
+        fs::create_dir_all(path.join("LOCK")).unwrap();
+        let err = DB::open_default(&path).unwrap_err();
+        assert_eq!(err.kind(), rocksdb::ErrorKind::IOError);
+        assert!(err.as_ref().contains("blockstore"));
+        assert!(!matches!(
+            lock_error(&path, err),
+            BlockstoreError::AlreadyInUse(_)
+        ));
+    }
+
+    #[test]
+    fn test_default_num_threads_for_access_type() {
+        assert_eq!(
+            default_num_compaction_threads_for_access_type(&AccessType::Primary),
+            default_num_compaction_threads()
+        );
+        assert_eq!(
+            default_num_compaction_threads_for_access_type(&AccessType::PrimaryForMaintenance),
+            NonZeroUsize::MIN
+        );
+        assert_eq!(
+            default_num_compaction_threads_for_access_type(&AccessType::Secondary),
+            NonZeroUsize::MIN
+        );
+
+        assert_eq!(
+            default_num_flush_threads_for_access_type(&AccessType::Primary),
+            default_num_flush_threads()
+        );
+        assert_eq!(
+            default_num_flush_threads_for_access_type(&AccessType::PrimaryForMaintenance),
+            default_num_flush_threads()
+        );
+        assert_eq!(
+            default_num_flush_threads_for_access_type(&AccessType::Secondary),
+            NonZeroUsize::MIN
+        );
+    }
+
+    #[test]
+    fn test_should_disable_auto_compactions() {
+        assert!(!should_disable_auto_compactions(
+            &AccessType::Primary,
+            false
+        ));
+        assert!(should_disable_auto_compactions(
+            &AccessType::PrimaryForMaintenance,
+            false
+        ));
+        assert!(!should_disable_auto_compactions(
+            &AccessType::PrimaryForMaintenance,
+            true
+        ));
+        // secondary instances can't compact
+        assert!(should_disable_auto_compactions(
+            &AccessType::Secondary,
+            true
+        ));
+    }
+
+    #[test]
+    fn test_max_open_files() {
+        assert_eq!(max_open_files(&BlockstoreOptions::default()), -1);
+
+        let options = BlockstoreOptions {
+            max_open_files: Some(1024),
+            ..BlockstoreOptions::default()
+        };
+        assert_eq!(max_open_files(&options), 1024);
+
+        // secondary instances require an unlimited number of open files
+        let options = BlockstoreOptions {
+            max_open_files: Some(1024),
+            ..BlockstoreOptions::with_access_type(AccessType::Secondary)
+        };
+        assert_eq!(max_open_files(&options), -1);
+    }
+
+    // rocksdb persists the options of the db and every column to the latest OPTIONS file
+    fn read_persisted_options(db_path: &Path) -> String {
+        let options_file = fs::read_dir(db_path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("OPTIONS-"))
+            })
+            .max()
+            .unwrap();
+        fs::read_to_string(options_file).unwrap()
+    }
+
+    #[test]
+    fn test_wal_size_and_log_retention() {
+        let db_path = TempDir::new().unwrap();
+        let _rocks = Rocks::open(
+            db_path.path().to_path_buf(),
+            BlockstoreOptions {
+                max_total_wal_size_bytes: 64 * 1024 * 1024,
+                keep_log_file_num: 3,
+                ..BlockstoreOptions::default()
+            },
+        )
+        .unwrap();
+
+        let persisted_options = read_persisted_options(db_path.path());
+        let db_option = |name: &str| {
+            persisted_options
+                .split_once("[DBOptions]")
+                .unwrap()
+                .1
+                .lines()
+                .find_map(|line| line.trim().strip_prefix(&format!("{name}=")))
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+        };
+        assert_eq!(db_option("max_total_wal_size"), 64 * 1024 * 1024);
+        assert_eq!(db_option("keep_log_file_num"), 3);
+    }
+
+    #[test]
+    fn test_write_buffer_size_override() {
+        const REWARDS_WRITE_BUFFER_SIZE: u64 = 1024 * 1024;
+        let column_options = LedgerColumnOptions {
+            write_buffer_size_overrides: HashMap::from([(
+                columns::Rewards::NAME.to_string(),
+                REWARDS_WRITE_BUFFER_SIZE,
+            )]),
+            ..LedgerColumnOptions::default()
+        };
+        assert_eq!(
+            get_write_buffer_size(columns::Rewards::NAME, &column_options),
+            REWARDS_WRITE_BUFFER_SIZE
+        );
+        assert_eq!(
+            get_write_buffer_size(columns::Blocktime::NAME, &column_options),
+            MAX_WRITE_BUFFER_SIZE
+        );
+
+        let db_path = TempDir::new().unwrap();
+        let _rocks = Rocks::open(
+            db_path.path().to_path_buf(),
+            BlockstoreOptions {
+                column_options,
+                ..BlockstoreOptions::default()
+            },
+        )
+        .unwrap();
+
+        let persisted_options = read_persisted_options(db_path.path());
+        let cf_write_buffer_size = |cf_name: &str| {
+            persisted_options
+                .split_once(&format!("[CFOptions \"{cf_name}\"]"))
+                .unwrap()
+                .1
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("write_buffer_size="))
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+        };
+        assert_eq!(
+            cf_write_buffer_size(columns::Rewards::NAME),
+            REWARDS_WRITE_BUFFER_SIZE
+        );
+        assert_eq!(
+            cf_write_buffer_size(columns::Blocktime::NAME),
+            MAX_WRITE_BUFFER_SIZE
+        );
+    }
+
+    #[test]
+    fn test_periodic_compaction_seconds() {
+        let db_path = TempDir::new().unwrap();
+        let _rocks = Rocks::open(
+            db_path.path().to_path_buf(),
+            BlockstoreOptions {
+                column_options: LedgerColumnOptions {
+                    periodic_compaction_seconds: 3600,
+                    ..LedgerColumnOptions::default()
+                },
+                ..BlockstoreOptions::default()
+            },
+        )
+        .unwrap();
+
+        let persisted_options = read_persisted_options(db_path.path());
+        let periodic_compaction_seconds = persisted_options
+            .split_once(&format!(
+                "[CFOptions \"{}\"]",
+                columns::TransactionStatus::NAME
+            ))
+            .unwrap()
+            .1
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("periodic_compaction_seconds="))
+            .unwrap()
+            .parse::<u64>()
+            .unwrap();
+        assert_eq!(periodic_compaction_seconds, 3600);
+    }
+
+    #[test]
+    fn test_extra_compaction_filtered_columns() {
+        let column_options = LedgerColumnOptions {
+            extra_compaction_filtered_columns: HashSet::from([columns::Rewards::NAME.to_string()]),
+            ..LedgerColumnOptions::default()
+        };
+        assert!(should_enable_cf_compaction(
+            columns::TransactionStatus::NAME,
+            &column_options
+        ));
+        assert!(should_enable_cf_compaction(
+            columns::Rewards::NAME,
+            &column_options
+        ));
+        assert!(!should_enable_cf_compaction(
+            columns::Rewards::NAME,
+            &LedgerColumnOptions::default()
+        ));
+
+        let db_path = TempDir::new().unwrap();
+        let _rocks = Rocks::open(
+            db_path.path().to_path_buf(),
+            BlockstoreOptions {
+                column_options,
+                ..BlockstoreOptions::default()
+            },
+        )
+        .unwrap();
+
+        let persisted_options = read_persisted_options(db_path.path());
+        let compaction_filter_factory = |cf_name: &str| {
+            persisted_options
+                .split_once(&format!("[CFOptions \"{cf_name}\"]"))
+                .unwrap()
+                .1
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("compaction_filter_factory="))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            compaction_filter_factory(columns::Rewards::NAME),
+            format!("purged_slot_filter_factory({})", columns::Rewards::NAME)
+        );
+        assert_eq!(
+            compaction_filter_factory(columns::Blocktime::NAME),
+            "nullptr"
+        );
+    }
+
+    #[test]
+    fn test_purge_decision() {
+        let is_kept = |slot_in_key, oldest_slot, clean_slot_0| {
+            matches!(
+                purge_decision(slot_in_key, oldest_slot, clean_slot_0),
+                CompactionDecision::Keep
+            )
+        };
+        assert!(!is_kept(9, 10, false));
+        assert!(is_kept(10, 10, false));
+        assert!(is_kept(11, 10, false));
+
+        // slot 0 is preserved unless asked to clean it up
+        assert!(is_kept(0, 10, false));
+        assert!(!is_kept(0, 10, true));
+        // nothing is purged while the oldest slot is 0
+        assert!(is_kept(0, 0, true));
+    }
+
+    #[test]
+    fn test_column_value_error() {
+        let db_path = TempDir::new().unwrap();
+        let rocks = Arc::new(
+            Rocks::open(db_path.path().to_path_buf(), BlockstoreOptions::default()).unwrap(),
+        );
+        let meta_cf = rocks.column::<columns::SlotMeta>();
+        let key = <columns::SlotMeta as Column>::key(&7);
+        rocks.put_cf(meta_cf.handle(), key, &[1, 2, 3]).unwrap();
+
+        let err = meta_cf.get(7).unwrap_err();
+        assert!(matches!(
+            &err,
+            BlockstoreError::ColumnValue {
+                column: columns::SlotMeta::NAME,
+                source,
+                ..
+            } if matches!(**source, BlockstoreError::Serialize(_))
+        ));
+        let message = err.to_string();
+        assert!(message.starts_with("meta column, key 0000000000000007: "));
+    }
+
+    #[test]
+    fn test_cf_descriptors_secondary_unknown_column() {
+        const UNKNOWN_COLUMN_NAME: &str = "unknown_column";
+        let db_path = TempDir::new().unwrap();
+        let db_path = db_path.path().to_path_buf();
+        drop(Rocks::open(db_path.clone(), BlockstoreOptions::default()).unwrap());
+
+        // Simulate a newer software version adding a column to the database
+        {
+            let cf_names = DB::list_cf(&Options::default(), &db_path).unwrap();
+            let mut db = DB::open_cf(&Options::default(), &db_path, cf_names).unwrap();
+            db.create_cf(UNKNOWN_COLUMN_NAME, &Options::default())
+                .unwrap();
+        }
+
+        let options = BlockstoreOptions::with_access_type(AccessType::Secondary);
+        let cf_descriptors = Rocks::cf_descriptors(&db_path, &options, &OldestSlot::default());
+        assert!(cf_descriptors
+            .iter()
+            .any(|cf_descriptor| cf_descriptor.name() == UNKNOWN_COLUMN_NAME));
+
+        let rocks = Rocks::open(db_path, options).unwrap();
+        assert!(rocks.db.cf_handle(UNKNOWN_COLUMN_NAME).is_some());
+    }
+}
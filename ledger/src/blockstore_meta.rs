@@ -5,11 +5,38 @@ use solana_hash::Hash;
 use std::collections::BTreeSet;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
-pub(crate) struct ErasureConfig {
+pub struct ErasureConfig {
     num_data: usize,
     num_coding: usize,
 }
 
+impl ErasureConfig {
+    /// Number of data shreds in the erasure set
+    pub fn num_data(&self) -> usize {
+        self.num_data
+    }
+
+    /// Number of coding shreds in the erasure set
+    pub fn num_coding(&self) -> usize {
+        self.num_coding
+    }
+
+    /// Total number of data and coding shreds in the erasure set
+    pub fn num_shreds(&self) -> usize {
+        self.num_data + self.num_coding
+    }
+}
+
+impl From<(usize, usize)> for ErasureConfig {
+    /// Creates the config from `(num_data, num_coding)`.
+    fn from((num_data, num_coding): (usize, usize)) -> Self {
+        Self {
+            num_data,
+            num_coding,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
 /// Erasure coding information
 pub struct ErasureMeta {
@@ -27,17 +54,46 @@ pub struct ErasureMeta {
     config: ErasureConfig,
 }
 
+impl ErasureMeta {
+    pub fn config(&self) -> ErasureConfig {
+        self.config
+    }
+}
+
+/// The outcome of a transaction, stored in the transaction status column
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TransactionStatusMeta {
+    /// `None` if the transaction succeeded, otherwise the error it failed with
+    pub err: Option<String>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+}
+
 #[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct TransactionStatusIndexMeta {
     pub max_slot: Slot,
     pub frozen: bool,
 }
 
+impl TransactionStatusIndexMeta {
+    /// The size of a bincode (fixint) serialized value: `max_slot` and `frozen`
+    pub(crate) const SERIALIZED_SIZE: u64 = 9;
+}
+
 #[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct AddressSignatureMeta {
     pub writeable: bool,
 }
 
+/// Performance statistics sampled by a validator over a period of time
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PerfSample {
+    pub num_transactions: u64,
+    pub num_slots: u64,
+    pub sample_period_secs: u16,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct MerkleRootMeta {
     /// The merkle root, `None` for legacy shreds
@@ -58,6 +114,25 @@ pub struct OptimisticSlotMetaV0 {
 pub enum OptimisticSlotMetaVersioned {
     V0(OptimisticSlotMetaV0),
 }
+
+impl OptimisticSlotMetaVersioned {
+    pub fn new(hash: Hash, timestamp: UnixTimestamp) -> Self {
+        OptimisticSlotMetaVersioned::V0(OptimisticSlotMetaV0 { hash, timestamp })
+    }
+
+    pub fn hash(&self) -> Hash {
+        match self {
+            OptimisticSlotMetaVersioned::V0(meta) => meta.hash,
+        }
+    }
+
+    pub fn timestamp(&self) -> UnixTimestamp {
+        match self {
+            OptimisticSlotMetaVersioned::V0(meta) => meta.timestamp,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
 /// The Meta column family
 pub struct SlotMetaBase<T> {
@@ -97,6 +172,41 @@ pub type CompletedDataIndexes = BTreeSet<u32>;
 
 pub type SlotMeta = SlotMetaBase<CompletedDataIndexes>;
 
+impl<T> SlotMetaBase<T> {
+    /// Whether all shreds of the slot, up to and including the last one, have been received.
+    pub fn is_full(&self) -> bool {
+        // last_index is None until the LAST_SHRED_IN_SLOT shred is received
+        let Some(last_index) = self.last_index else {
+            return false;
+        };
+        self.consumed == last_index + 1
+    }
+
+    pub fn is_parent_set(&self) -> bool {
+        self.parent_slot.is_some()
+    }
+
+    /// Whether the slot can be chained to its parent: its parent is known and
+    /// it is full. Whether the parent itself chains back to the root has to be
+    /// checked against the parent's meta.
+    pub fn is_connected(&self) -> bool {
+        self.is_parent_set() && self.is_full()
+    }
+
+    /// Slots which derive from this one, in ascending order.
+    pub fn children(&self) -> &[Slot] {
+        &self.next_slots
+    }
+
+    /// Links `slot` as a child of this slot, keeping `next_slots` sorted and
+    /// free of duplicates.
+    pub fn add_next_slot(&mut self, slot: Slot) {
+        if let Err(index) = self.next_slots.binary_search(&slot) {
+            self.next_slots.insert(index, slot);
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Index {
     pub slot: Slot,
@@ -104,12 +214,52 @@ pub struct Index {
     coding: ShredIndex,
 }
 
+impl Index {
+    pub fn new(slot: Slot) -> Self {
+        Index {
+            slot,
+            data: ShredIndex::default(),
+            coding: ShredIndex::default(),
+        }
+    }
+
+    pub fn data(&self) -> &ShredIndex {
+        &self.data
+    }
+
+    pub fn coding(&self) -> &ShredIndex {
+        &self.coding
+    }
+
+    pub fn data_mut(&mut self) -> &mut ShredIndex {
+        &mut self.data
+    }
+
+    pub fn coding_mut(&mut self) -> &mut ShredIndex {
+        &mut self.coding
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct ShredIndex {
     /// Map representing presence/absence of shreds
     index: BTreeSet<u64>,
 }
 
+impl ShredIndex {
+    pub fn num_shreds(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn contains(&self, index: u64) -> bool {
+        self.index.contains(&index)
+    }
+
+    pub fn insert(&mut self, index: u64) {
+        self.index.insert(index);
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct FrozenHashStatus {
     pub frozen_hash: Hash,
@@ -121,6 +271,22 @@ pub enum FrozenHashVersioned {
     Current(FrozenHashStatus),
 }
 
+impl FrozenHashVersioned {
+    pub fn frozen_hash(&self) -> Hash {
+        match self {
+            FrozenHashVersioned::Current(frozen_hash_status) => frozen_hash_status.frozen_hash,
+        }
+    }
+
+    pub fn is_duplicate_confirmed(&self) -> bool {
+        match self {
+            FrozenHashVersioned::Current(frozen_hash_status) => {
+                frozen_hash_status.is_duplicate_confirmed
+            }
+        }
+    }
+}
+
 // Helper module to serde values by type-casting to an intermediate
 // type for backward compatibility.
 mod serde_compat_cast {
@@ -176,3 +342,113 @@ mod serde_compat {
         Ok((val != u64::MAX).then_some(val))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erasure_config() {
+        let config = ErasureConfig::from((32, 17));
+        assert_eq!(config.num_data(), 32);
+        assert_eq!(config.num_coding(), 17);
+        assert_eq!(config.num_shreds(), 49);
+        assert_eq!(ErasureConfig::from((0, 0)).num_shreds(), 0);
+    }
+
+    #[test]
+    fn test_transaction_status_index_meta_serialized_size() {
+        let meta = TransactionStatusIndexMeta {
+            max_slot: Slot::MAX,
+            frozen: true,
+        };
+        assert_eq!(
+            bincode::serialized_size(&meta).unwrap(),
+            TransactionStatusIndexMeta::SERIALIZED_SIZE
+        );
+    }
+
+    #[test]
+    fn test_index() {
+        let mut index = Index::new(42);
+        assert_eq!(index.slot, 42);
+        assert_eq!(index.data().num_shreds(), 0);
+        assert_eq!(index.coding().num_shreds(), 0);
+
+        for i in [0, 1, 5] {
+            index.data_mut().insert(i);
+        }
+        index.coding_mut().insert(3);
+        // duplicate inserts are no-ops
+        index.coding_mut().insert(3);
+
+        assert_eq!(index.data().num_shreds(), 3);
+        assert!(index.data().contains(5));
+        assert!(!index.data().contains(3));
+        assert_eq!(index.coding().num_shreds(), 1);
+        assert!(index.coding().contains(3));
+        assert!(!index.coding().contains(0));
+    }
+
+    #[test]
+    fn test_slot_meta_incomplete() {
+        let slot_meta = SlotMeta {
+            slot: 5,
+            consumed: 3,
+            received: 8,
+            last_index: Some(7),
+            parent_slot: Some(4),
+            ..SlotMeta::default()
+        };
+        assert!(!slot_meta.is_full());
+        assert!(slot_meta.is_parent_set());
+        assert!(!slot_meta.is_connected());
+    }
+
+    #[test]
+    fn test_slot_meta_full() {
+        let slot_meta = SlotMeta {
+            slot: 5,
+            consumed: 8,
+            received: 8,
+            last_index: Some(7),
+            parent_slot: Some(4),
+            ..SlotMeta::default()
+        };
+        assert!(slot_meta.is_full());
+        assert!(slot_meta.is_connected());
+
+        let slot_meta = SlotMeta {
+            parent_slot: None,
+            ..slot_meta
+        };
+        assert!(slot_meta.is_full());
+        assert!(!slot_meta.is_parent_set());
+        assert!(!slot_meta.is_connected());
+    }
+
+    #[test]
+    fn test_slot_meta_missing_last_index() {
+        let slot_meta = SlotMeta {
+            slot: 5,
+            consumed: 8,
+            received: 8,
+            last_index: None,
+            parent_slot: Some(4),
+            ..SlotMeta::default()
+        };
+        assert!(!slot_meta.is_full());
+        assert!(!slot_meta.is_connected());
+    }
+
+    #[test]
+    fn test_slot_meta_add_next_slot() {
+        let mut slot_meta = SlotMeta::default();
+        assert!(slot_meta.children().is_empty());
+
+        for slot in [5, 2, 9, 2, 5, 7] {
+            slot_meta.add_next_slot(slot);
+        }
+        assert_eq!(slot_meta.children(), &[2, 5, 7, 9]);
+    }
+}
@@ -1,10 +1,30 @@
-use crate::blockstore_db::{default_num_compaction_threads, default_num_flush_threads};
+use crate::blockstore_db::{
+    default_num_compaction_threads, default_num_compaction_threads_for_access_type,
+    default_num_flush_threads, default_num_flush_threads_for_access_type,
+};
 use rocksdb::{DBCompressionType as RocksCompressionType, DBRecoveryMode};
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 
 /// The subdirectory under ledger directory where the Blockstore lives
 pub const BLOCKSTORE_DIRECTORY_ROCKS_LEVEL: &str = "rocksdb";
 
+/// Default maximum total size of the rocksdb write-ahead logs
+pub const DEFAULT_MAX_TOTAL_WAL_SIZE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Default number of rocksdb info log files to keep
+pub const DEFAULT_KEEP_LOG_FILE_NUM: usize = 10;
+
+/// Default age in seconds after which SST files of the columns cleaned up by
+/// compaction are picked up for compaction.
+///
+/// One day strikes a balance between storage getting reclaimed in a timely
+/// manner and the additional I/O that compaction incurs. For more details on
+/// this property, see
+/// https://github.com/facebook/rocksdb/blob/749b179c041347d150fa6721992ae8398b7d2b39/
+///   include/rocksdb/advanced_options.h#L908C30-L908C30
+pub const DEFAULT_PERIODIC_COMPACTION_SECONDS: u64 = 60 * 60 * 24;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockstoreOptions {
     // The access type of blockstore. Default: Primary
@@ -17,6 +37,20 @@ pub struct BlockstoreOptions {
     pub column_options: LedgerColumnOptions,
     pub num_rocksdb_compaction_threads: NonZeroUsize,
     pub num_rocksdb_flush_threads: NonZeroUsize,
+    // The maximum number of files rocksdb keeps open. Ignored for Secondary
+    // access, which requires an unlimited number. Default: None (unlimited).
+    pub max_open_files: Option<i32>,
+    // The maximum total size of rocksdb write-ahead logs. Default: 4 GiB.
+    pub max_total_wal_size_bytes: u64,
+    // The number of rocksdb info log files to keep. Default: 10.
+    pub keep_log_file_num: usize,
+    // Whether to leave rocksdb automatic compactions enabled under
+    // PrimaryForMaintenance access, e.g. to let maintenance reclaim the space
+    // of purged slots. Default: false.
+    pub enable_auto_compactions_for_maintenance: bool,
+    // The number of slots tracked by the blockstore's slot stats cache.
+    // Default: None (300 slots).
+    pub slots_stats_capacity: Option<NonZeroUsize>,
 }
 
 impl Default for BlockstoreOptions {
@@ -31,6 +65,26 @@ impl Default for BlockstoreOptions {
             column_options: LedgerColumnOptions::default(),
             num_rocksdb_compaction_threads: default_num_compaction_threads(),
             num_rocksdb_flush_threads: default_num_flush_threads(),
+            max_open_files: None,
+            max_total_wal_size_bytes: DEFAULT_MAX_TOTAL_WAL_SIZE_BYTES,
+            keep_log_file_num: DEFAULT_KEEP_LOG_FILE_NUM,
+            enable_auto_compactions_for_maintenance: false,
+            slots_stats_capacity: None,
+        }
+    }
+}
+
+impl BlockstoreOptions {
+    /// The default options for the given access type, with rocksdb threadpools
+    /// sized for what that access type actually needs.
+    pub fn with_access_type(access_type: AccessType) -> Self {
+        Self {
+            num_rocksdb_compaction_threads: default_num_compaction_threads_for_access_type(
+                &access_type,
+            ),
+            num_rocksdb_flush_threads: default_num_flush_threads_for_access_type(&access_type),
+            access_type,
+            ..Self::default()
         }
     }
 }
@@ -39,7 +93,9 @@ impl Default for BlockstoreOptions {
 pub enum AccessType {
     /// Primary (read/write) access; only one process can have Primary access.
     Primary,
-    /// Primary (read/write) access with RocksDB automatic compaction disabled.
+    /// Primary (read/write) access with RocksDB automatic compaction disabled,
+    /// unless [`BlockstoreOptions::enable_auto_compactions_for_maintenance`]
+    /// is set.
     PrimaryForMaintenance,
     /// Secondary (read) access; multiple processes can have Secondary access.
     /// Additionally, Secondary access can be obtained while another process
@@ -87,7 +143,7 @@ impl From<BlockstoreRecoveryMode> for DBRecoveryMode {
 /// Options for LedgerColumn.
 /// Each field might also be used as a tag that supports group-by operation when
 /// reporting metrics.
-#[derive(Default, Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LedgerColumnOptions {
     // Determine the way to compress column families which are eligible for
     // compression.
@@ -97,6 +153,32 @@ pub struct LedgerColumnOptions {
     // If the value is greater than 0, then RocksDB read/write perf sample
     // will be collected once for every `rocks_perf_sample_interval` ops.
     pub rocks_perf_sample_interval: usize,
+
+    // Overrides of the memtable write buffer size (in bytes) keyed by column
+    // name. Columns without an override use the default write buffer size;
+    // rarely written columns can be shrunk to save memory.
+    pub write_buffer_size_overrides: HashMap<String, u64>,
+
+    // SST files of the columns that rely on compaction for cleanup are picked
+    // up for compaction once they are older than this many seconds.
+    pub periodic_compaction_seconds: u64,
+
+    // Names of columns to clean up by compaction, in addition to the columns
+    // that always are. Useful to reclaim the storage of a column whose keys
+    // start with the slot but that isn't otherwise cleaned up.
+    pub extra_compaction_filtered_columns: HashSet<String>,
+}
+
+impl Default for LedgerColumnOptions {
+    fn default() -> Self {
+        Self {
+            compression_type: BlockstoreCompressionType::default(),
+            rocks_perf_sample_interval: 0,
+            write_buffer_size_overrides: HashMap::default(),
+            periodic_compaction_seconds: DEFAULT_PERIODIC_COMPACTION_SECONDS,
+            extra_compaction_filtered_columns: HashSet::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -0,0 +1,55 @@
+//! Slot and epoch conversions for labeling blockstore slots by epoch.
+
+use solana_clock::{Epoch, Slot};
+use solana_epoch_schedule::EpochSchedule;
+
+/// Returns the epoch `slot` belongs to.
+pub fn epoch_of_slot(epoch_schedule: &EpochSchedule, slot: Slot) -> Epoch {
+    epoch_schedule.get_epoch(slot)
+}
+
+/// Returns the first slot of `epoch`.
+pub fn first_slot_in_epoch(epoch_schedule: &EpochSchedule, epoch: Epoch) -> Slot {
+    epoch_schedule.get_first_slot_in_epoch(epoch)
+}
+
+/// Returns the number of slots of the epoch of `slot` from `slot` on, `slot`
+/// included.
+pub fn slots_remaining_in_epoch(epoch_schedule: &EpochSchedule, slot: Slot) -> u64 {
+    let (epoch, slot_index) = epoch_schedule.get_epoch_and_slot_index(slot);
+    epoch_schedule.get_slots_in_epoch(epoch) - slot_index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_warmup() {
+        let epoch_schedule = EpochSchedule::custom(100, 100, false);
+        assert_eq!(epoch_of_slot(&epoch_schedule, 0), 0);
+        assert_eq!(epoch_of_slot(&epoch_schedule, 99), 0);
+        assert_eq!(epoch_of_slot(&epoch_schedule, 100), 1);
+        assert_eq!(first_slot_in_epoch(&epoch_schedule, 0), 0);
+        assert_eq!(first_slot_in_epoch(&epoch_schedule, 3), 300);
+        assert_eq!(slots_remaining_in_epoch(&epoch_schedule, 0), 100);
+        assert_eq!(slots_remaining_in_epoch(&epoch_schedule, 250), 50);
+        assert_eq!(slots_remaining_in_epoch(&epoch_schedule, 299), 1);
+    }
+
+    #[test]
+    fn test_with_warmup() {
+        // epochs 0, 1 and 2 have 32, 64 and 128 slots; 256 from epoch 3 on
+        let epoch_schedule = EpochSchedule::custom(256, 256, true);
+        assert_eq!(epoch_of_slot(&epoch_schedule, 31), 0);
+        assert_eq!(epoch_of_slot(&epoch_schedule, 32), 1);
+        assert_eq!(epoch_of_slot(&epoch_schedule, 224), 3);
+        assert_eq!(epoch_of_slot(&epoch_schedule, 480), 4);
+        assert_eq!(first_slot_in_epoch(&epoch_schedule, 1), 32);
+        assert_eq!(first_slot_in_epoch(&epoch_schedule, 2), 96);
+        assert_eq!(first_slot_in_epoch(&epoch_schedule, 4), 480);
+        assert_eq!(slots_remaining_in_epoch(&epoch_schedule, 0), 32);
+        assert_eq!(slots_remaining_in_epoch(&epoch_schedule, 40), 56);
+        assert_eq!(slots_remaining_in_epoch(&epoch_schedule, 479), 1);
+    }
+}
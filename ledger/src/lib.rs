@@ -1,7 +1,8 @@
 pub mod blockstore;
 mod blockstore_db;
-mod blockstore_meta;
+pub mod blockstore_meta;
 mod blockstore_metrics;
 pub mod blockstore_options;
-mod shred;
+pub mod epoch_utils;
+pub mod shred;
 mod slot_stats;
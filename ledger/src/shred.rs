@@ -1,8 +1,21 @@
+use bitflags::bitflags;
+use blockchain_feature_set::FeatureSet;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
+use solana_hash::Hash;
+use solana_sha256_hasher::hashv;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
 
 pub const MAX_DATA_SHREDS_PER_SLOT: usize = 32_768;
 
+/// Returns whether `index` fits within [`MAX_DATA_SHREDS_PER_SLOT`]. A data
+/// shred beyond it can only come from a corrupted or malicious leader.
+pub fn is_valid_data_shred_index(index: u64) -> bool {
+    index < MAX_DATA_SHREDS_PER_SLOT as u64
+}
+
 #[repr(u8)]
 #[derive(
     Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, IntoPrimitive, Serialize, TryFromPrimitive,
@@ -12,3 +25,168 @@ pub enum ShredType {
     Data = 0b1010_0101,
     Code = 0b0101_1010,
 }
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid shred type {0:?}, expected \"data\" or \"code\"")]
+pub struct ParseShredTypeError(String);
+
+impl fmt::Display for ShredType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ShredType::Data => "data",
+            ShredType::Code => "code",
+        })
+    }
+}
+
+impl FromStr for ShredType {
+    type Err = ParseShredTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "data" => Ok(ShredType::Data),
+            "code" => Ok(ShredType::Code),
+            _ => Err(ParseShredTypeError(s.to_string())),
+        }
+    }
+}
+
+bitflags! {
+    /// Flags carried in the header of a data shred.
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct ShredFlags: u8 {
+        /// Ticks since the parent slot, saturating at the mask.
+        const SHRED_TICK_REFERENCE_MASK = 0b0011_1111;
+        /// The shred completes a batch of entries.
+        const DATA_COMPLETE_SHRED       = 0b0100_0000;
+        /// The shred is the last one of its slot; implies DATA_COMPLETE_SHRED.
+        const LAST_SHRED_IN_SLOT        = 0b1100_0000;
+    }
+}
+
+/// Folds `hash` into a shred version, which is never zero so that it can't be
+/// mistaken for an unknown version.
+pub fn version_from_hash(hash: &Hash) -> u16 {
+    let hash = hash.as_ref();
+    let mut accum = [0u8; 2];
+    hash.chunks(2).for_each(|seed| {
+        accum
+            .iter_mut()
+            .zip(seed)
+            .for_each(|(accum, seed)| *accum ^= *seed)
+    });
+    // convert accum into a u16
+    let version = ((accum[0] as u16) << 8) | accum[1] as u16;
+
+    // ensure version is never zero, to avoid being interpreted as an unknown version
+    version.saturating_add(1)
+}
+
+/// Computes the shred version of a cluster from its genesis hash and, if
+/// given, the ids of its active features. Feature ids are mixed in sorted so
+/// the version doesn't depend on the iteration order of the feature set.
+pub fn compute_shred_version(genesis_hash: &Hash, feature_set: Option<&FeatureSet>) -> u16 {
+    let mut hash = *genesis_hash;
+    if let Some(feature_set) = feature_set {
+        let mut feature_ids: Vec<_> = feature_set.active().keys().collect();
+        feature_ids.sort_unstable();
+        for feature_id in feature_ids {
+            hash = hashv(&[hash.as_ref(), feature_id.as_ref()]);
+        }
+    }
+    version_from_hash(&hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_pubkey::Pubkey;
+
+    #[test]
+    fn test_is_valid_data_shred_index() {
+        assert!(is_valid_data_shred_index(0));
+        assert!(is_valid_data_shred_index(
+            MAX_DATA_SHREDS_PER_SLOT as u64 - 1
+        ));
+        assert!(!is_valid_data_shred_index(MAX_DATA_SHREDS_PER_SLOT as u64));
+        assert!(!is_valid_data_shred_index(u64::MAX));
+    }
+
+    #[test]
+    fn test_shred_type_display_and_parse() {
+        assert_eq!(ShredType::Data.to_string(), "data");
+        assert_eq!(ShredType::Code.to_string(), "code");
+        for shred_type in [ShredType::Data, ShredType::Code] {
+            assert_eq!(shred_type.to_string().parse(), Ok(shred_type));
+        }
+        assert_eq!(
+            "Data".parse::<ShredType>(),
+            Err(ParseShredTypeError("Data".to_string()))
+        );
+        assert_eq!(
+            "coding".parse::<ShredType>().unwrap_err().to_string(),
+            "invalid shred type \"coding\", expected \"data\" or \"code\""
+        );
+        // The serialized form is still the u8 discriminant
+        assert_eq!(
+            bincode::serialize(&ShredType::Data).unwrap(),
+            vec![0b1010_0101]
+        );
+    }
+
+    #[test]
+    fn test_version_from_hash() {
+        assert_eq!(version_from_hash(&Hash::default()), 1);
+        // Every pair of bytes cancels out
+        assert_eq!(version_from_hash(&Hash::new_from_array([0xa5; 32])), 1);
+
+        let mut hash = [0; 32];
+        hash[0] = 0x12;
+        hash[1] = 0x34;
+        assert_eq!(version_from_hash(&Hash::new_from_array(hash)), 0x1235);
+
+        // Saturates instead of wrapping to zero
+        hash[0] = 0xff;
+        hash[1] = 0xff;
+        assert_eq!(version_from_hash(&Hash::new_from_array(hash)), u16::MAX);
+    }
+
+    #[test]
+    fn test_compute_shred_version() {
+        let genesis_hash = Hash::new_from_array([7; 32]);
+        assert_eq!(
+            compute_shred_version(&genesis_hash, None),
+            version_from_hash(&genesis_hash)
+        );
+
+        let mut feature_set = FeatureSet::default();
+        // No active features leaves the genesis hash untouched
+        assert_eq!(
+            compute_shred_version(&genesis_hash, Some(&feature_set)),
+            compute_shred_version(&genesis_hash, None)
+        );
+
+        let feature_a = Pubkey::new_from_array([1; 32]);
+        let feature_b = Pubkey::new_from_array([2; 32]);
+        feature_set.activate(&feature_a, 0);
+        let version = compute_shred_version(&genesis_hash, Some(&feature_set));
+        assert_eq!(
+            compute_shred_version(&genesis_hash, Some(&feature_set.clone())),
+            version
+        );
+        assert_ne!(version, compute_shred_version(&genesis_hash, None));
+
+        feature_set.activate(&feature_b, 0);
+        let version_with_both = compute_shred_version(&genesis_hash, Some(&feature_set));
+        assert_ne!(version_with_both, version);
+
+        // Activation order doesn't matter
+        let mut other_feature_set = FeatureSet::default();
+        other_feature_set.activate(&feature_b, 0);
+        other_feature_set.activate(&feature_a, 0);
+        assert_eq!(
+            compute_shred_version(&genesis_hash, Some(&other_feature_set)),
+            version_with_both
+        );
+    }
+}
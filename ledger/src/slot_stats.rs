@@ -23,6 +23,12 @@ pub struct SlotStats {
     flags: SlotFlags,
 }
 
+impl SlotStats {
+    pub fn is_dead(&self) -> bool {
+        self.flags.contains(SlotFlags::DEAD)
+    }
+}
+
 pub struct SlotsStats {
     pub stats: Mutex<LruCache<Slot, SlotStats>>,
 }
@@ -31,8 +37,49 @@ const SLOTS_STATS_CACHE_CAPACITY: NonZeroUsize = NonZeroUsize::new(300).unwrap()
 
 impl Default for SlotsStats {
     fn default() -> Self {
+        Self::with_capacity(SLOTS_STATS_CACHE_CAPACITY)
+    }
+}
+
+impl SlotsStats {
+    /// Creates stats that track up to `capacity` slots, evicting the least
+    /// recently used slot beyond that.
+    pub fn with_capacity(capacity: NonZeroUsize) -> Self {
         Self {
-            stats: Mutex::new(LruCache::new(SLOTS_STATS_CACHE_CAPACITY)),
+            stats: Mutex::new(LruCache::new(capacity)),
         }
     }
+
+    /// Flags `slot` as dead.
+    pub fn mark_dead(&self, slot: Slot) {
+        let mut stats = self.stats.lock().unwrap();
+        let slot_stats = stats.get_or_insert_mut(slot, SlotStats::default);
+        slot_stats.flags |= SlotFlags::DEAD;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_capacity() {
+        assert_eq!(
+            SlotsStats::default().stats.lock().unwrap().cap(),
+            SLOTS_STATS_CACHE_CAPACITY
+        );
+
+        let slots_stats = SlotsStats::with_capacity(NonZeroUsize::new(3).unwrap());
+        for slot in 0..3 {
+            slots_stats.mark_dead(slot);
+        }
+        assert_eq!(slots_stats.stats.lock().unwrap().len(), 3);
+
+        // The oldest slot is evicted once the capacity is exceeded
+        slots_stats.mark_dead(3);
+        let stats = slots_stats.stats.lock().unwrap();
+        assert_eq!(stats.len(), 3);
+        assert!(!stats.contains(&0));
+        assert!((1..=3).all(|slot| stats.contains(&slot)));
+    }
 }
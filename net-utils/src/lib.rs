@@ -30,8 +30,12 @@ use {
     rand::{thread_rng, Rng},
     socket2::SockAddr,
     std::{
+        collections::HashMap,
         io::{self},
-        net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, ToSocketAddrs, UdpSocket},
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, ToSocketAddrs, UdpSocket},
+        sync::{mpsc, LazyLock, Mutex},
+        thread,
+        time::{Duration, Instant},
     },
     url::Url,
 };
@@ -211,7 +215,22 @@ pub fn parse_port_range(port_range: &str) -> Option<PortRange> {
 }
 
 pub fn parse_host(host: &str) -> Result<IpAddr, String> {
-    // First, check if the host syntax is valid. This check is needed because addresses
+    // IP literals need no resolution. IPv6 literals must be checked before the
+    // URL below, which only accepts them in brackets, e.g. `[::1]`.
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(ip);
+    }
+    if let Some(ipv6) = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+    {
+        return ipv6
+            .parse::<Ipv6Addr>()
+            .map(IpAddr::V6)
+            .map_err(|err| format!("Invalid IPv6 address {host}: {err}"));
+    }
+
+    // Otherwise, check if the host syntax is valid. This check is needed because addresses
     // such as `("localhost:1234", 0)` will resolve to IPs on some networks.
     let parsed_url = Url::parse(&format!("http://{host}")).map_err(|e| e.to_string())?;
     if parsed_url.port().is_some() {
@@ -231,11 +250,167 @@ pub fn parse_host(host: &str) -> Result<IpAddr, String> {
     }
 }
 
+/// Parses a host whose address can be advertised to other nodes, such as the
+/// gossip host. Unspecified (e.g. `0.0.0.0`) and multicast addresses are
+/// rejected since no peer could reach the node through them.
+pub fn parse_advertisable_host(host: &str) -> Result<IpAddr, String> {
+    parse_advertisable_host_impl(host, /*allow_loopback:*/ true)
+}
+
+/// Like [`parse_advertisable_host`], but additionally rejects loopback
+/// addresses, which are only reachable from the local machine.
+pub fn parse_public_advertisable_host(host: &str) -> Result<IpAddr, String> {
+    parse_advertisable_host_impl(host, /*allow_loopback:*/ false)
+}
+
+fn parse_advertisable_host_impl(host: &str, allow_loopback: bool) -> Result<IpAddr, String> {
+    let ip = parse_host(host)?;
+    match classify_addr(ip) {
+        AddrClass::Unspecified => Err(format!(
+            "Unspecified address {ip} cannot be advertised: {host}"
+        )),
+        AddrClass::Multicast => Err(format!(
+            "Multicast address {ip} cannot be advertised: {host}"
+        )),
+        AddrClass::Loopback if !allow_loopback => Err(format!(
+            "Loopback address {ip} cannot be advertised: {host}"
+        )),
+        AddrClass::Loopback | AddrClass::Private | AddrClass::Global => Ok(ip),
+    }
+}
+
+/// The reachability class of an IP address, see [`classify_addr`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddrClass {
+    /// Only reachable from the local machine, e.g. `127.0.0.1` or `::1`
+    Loopback,
+    /// The "any" address, e.g. `0.0.0.0` or `::`
+    Unspecified,
+    Multicast,
+    /// Private or link-local networks, e.g. `10.0.0.0/8` or `fc00::/7`
+    Private,
+    /// Everything else
+    Global,
+}
+
+/// Classifies `ip` by reachability. IPv4-mapped IPv6 addresses are classified
+/// as the IPv4 address they map.
+pub fn classify_addr(ip: IpAddr) -> AddrClass {
+    let ip = ip.to_canonical();
+    if ip.is_loopback() {
+        return AddrClass::Loopback;
+    }
+    if ip.is_unspecified() {
+        return AddrClass::Unspecified;
+    }
+    if ip.is_multicast() {
+        return AddrClass::Multicast;
+    }
+    let is_private = match ip {
+        IpAddr::V4(ip) => ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => ip.is_unique_local() || ip.is_unicast_link_local(),
+    };
+    if is_private {
+        AddrClass::Private
+    } else {
+        AddrClass::Global
+    }
+}
+
 pub fn is_host(string: String) -> Result<(), String> {
     parse_host(&string).map(|_| ())
 }
 
+/// How long [`parse_host_port`] waits for the host to resolve
+pub const DEFAULT_RESOLVE_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub fn parse_host_port(host_port: &str) -> Result<SocketAddr, String> {
+    parse_host_port_with_timeout(host_port, DEFAULT_RESOLVE_TIMEOUT)
+}
+
+/// Like [`parse_host_port`], but fails if resolving the host takes longer than
+/// `timeout`, e.g. because of an unresponsive DNS server.
+pub fn parse_host_port_with_timeout(
+    host_port: &str,
+    timeout: Duration,
+) -> Result<SocketAddr, String> {
+    let owned_host_port = host_port.to_string();
+    match resolve_with_timeout(move || resolve_host_port(&owned_host_port), timeout) {
+        Ok(Some(result)) => result,
+        Ok(None) => Err(format!(
+            "Timed out resolving host {host_port} after {timeout:?}"
+        )),
+        Err(err) => Err(format!(
+            "Unable to spawn a thread to resolve host {host_port}: {err}"
+        )),
+    }
+}
+
+/// How long [`parse_host_port_cached`] reuses the address a host resolved to
+pub const DEFAULT_RESOLVE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+static RESOLVE_CACHE: LazyLock<ResolveCache> =
+    LazyLock::new(|| ResolveCache::new(DEFAULT_RESOLVE_CACHE_TTL));
+
+/// Like [`parse_host_port`], but reuses the address the host resolved to in
+/// the last [`DEFAULT_RESOLVE_CACHE_TTL`], so that many entrypoints on the same
+/// host are only looked up once. The cache is shared by the whole process.
+pub fn parse_host_port_cached(host_port: &str) -> Result<SocketAddr, String> {
+    RESOLVE_CACHE.resolve(host_port, Instant::now(), parse_host_port)
+}
+
+/// Addresses that hosts resolved to, keyed by host, which expire after `ttl`.
+struct ResolveCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (IpAddr, Instant)>>,
+}
+
+impl ResolveCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Returns the address of `host_port`, calling `resolve` unless its host
+    /// was resolved less than `ttl` before `now`.
+    fn resolve(
+        &self,
+        host_port: &str,
+        now: Instant,
+        resolve: impl FnOnce(&str) -> Result<SocketAddr, String>,
+    ) -> Result<SocketAddr, String> {
+        let (host, port) = host_port
+            .rsplit_once(':')
+            .ok_or_else(|| format!("Missing port in {host_port}"))?;
+        let port = port
+            .parse::<u16>()
+            .map_err(|err| format!("Invalid port in {host_port}: {err}"))?;
+
+        let cached_ip = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(host)
+            .filter(|(_, resolved_at)| now.saturating_duration_since(*resolved_at) < self.ttl)
+            .map(|(ip, _)| *ip);
+        if let Some(ip) = cached_ip {
+            return Ok(SocketAddr::new(ip, port));
+        }
+
+        // The lock isn't held while resolving, so a slow lookup doesn't hold
+        // up the other hosts
+        let addr = resolve(host_port)?;
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(host.to_string(), (addr.ip(), now));
+        Ok(addr)
+    }
+}
+
+fn resolve_host_port(host_port: &str) -> Result<SocketAddr, String> {
     let addrs: Vec<_> = host_port
         .to_socket_addrs()
         .map_err(|err| format!("Unable to resolve host {host_port}: {err}"))?
@@ -247,6 +422,23 @@ pub fn parse_host_port(host_port: &str) -> Result<SocketAddr, String> {
     }
 }
 
+// Runs the blocking `resolve` on a helper thread, returning `None` if it does
+// not complete within `timeout`. The helper thread is left to finish on its own.
+// Fails if the helper thread can't be spawned.
+fn resolve_with_timeout<T: Send + 'static>(
+    resolve: impl FnOnce() -> T + Send + 'static,
+    timeout: Duration,
+) -> io::Result<Option<T>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("solResolveHost".to_string())
+        .spawn(move || {
+            // The receiver is gone if the resolution timed out
+            let _ = sender.send(resolve());
+        })?;
+    Ok(receiver.recv_timeout(timeout).ok())
+}
+
 pub fn is_host_port(string: String) -> Result<(), String> {
     parse_host_port(&string).map(|_| ())
 }
@@ -583,6 +775,35 @@ pub fn find_available_ports_in_range<const N: usize>(
     Ok(result)
 }
 
+/// Binds `count` UDP sockets to distinct ports in the provided range in a
+/// single pass.
+///
+/// Unlike [`find_available_ports_in_range`], the sockets are returned still
+/// bound, so the ports stay reserved for as long as the caller holds them.
+pub fn allocate_validator_ports(
+    ip_addr: IpAddr,
+    range: PortRange,
+    count: usize,
+) -> io::Result<Vec<(u16, UdpSocket)>> {
+    let config = sockets::SocketConfiguration::default();
+    let mut result = Vec::with_capacity(count);
+    for port in range.0..range.1 {
+        if result.len() == count {
+            break;
+        }
+        if let Ok(socket) = sockets::bind_to_with_config(ip_addr, port, config) {
+            result.push((port, socket));
+        }
+    }
+    if result.len() < count {
+        return Err(io::Error::other(format!(
+            "Only {} of {count} UDP ports available in {range:?}",
+            result.len()
+        )));
+    }
+    Ok(result)
+}
+
 #[deprecated(
     since = "3.0.0",
     note = "Please avoid this function, in favor of sockets::bind_more_with_config"
@@ -694,7 +915,65 @@ mod tests {
         parse_host("localhost:1234").unwrap_err();
         parse_host("localhost").unwrap();
         parse_host("127.0.0.0:1234").unwrap_err();
-        parse_host("127.0.0.0").unwrap();
+        assert_eq!(parse_host("127.0.0.0"), Ok(IpAddr::from([127, 0, 0, 0])));
+    }
+
+    #[test]
+    fn test_parse_host_ipv6() {
+        assert_eq!(parse_host("::1"), Ok(IpAddr::from(Ipv6Addr::LOCALHOST)));
+        assert_eq!(parse_host("[::1]"), Ok(IpAddr::from(Ipv6Addr::LOCALHOST)));
+        assert_eq!(parse_host("::"), Ok(IpAddr::from(Ipv6Addr::UNSPECIFIED)));
+        parse_host("[::1]:1234").unwrap_err();
+        parse_host("[127.0.0.1]").unwrap_err();
+        parse_host("[::1").unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_advertisable_host() {
+        assert_eq!(
+            parse_advertisable_host("8.8.8.8"),
+            Ok(IpAddr::from([8, 8, 8, 8]))
+        );
+        assert_eq!(
+            parse_public_advertisable_host("8.8.8.8"),
+            Ok(IpAddr::from([8, 8, 8, 8]))
+        );
+        parse_advertisable_host("8.8.8.8:1234").unwrap_err();
+
+        parse_advertisable_host("0.0.0.0").unwrap_err();
+        parse_advertisable_host("::").unwrap_err();
+        parse_advertisable_host("224.0.0.1").unwrap_err();
+        parse_advertisable_host("ff02::1").unwrap_err();
+
+        assert_eq!(
+            parse_advertisable_host("127.0.0.1"),
+            Ok(IpAddr::from(Ipv4Addr::LOCALHOST))
+        );
+        parse_public_advertisable_host("127.0.0.1").unwrap_err();
+    }
+
+    #[test]
+    fn test_classify_addr() {
+        let classify = |ip: &str| classify_addr(ip.parse().unwrap());
+
+        assert_eq!(classify("127.0.0.1"), AddrClass::Loopback);
+        assert_eq!(classify("::1"), AddrClass::Loopback);
+        assert_eq!(classify("::ffff:127.0.0.1"), AddrClass::Loopback);
+
+        assert_eq!(classify("0.0.0.0"), AddrClass::Unspecified);
+        assert_eq!(classify("::"), AddrClass::Unspecified);
+
+        assert_eq!(classify("224.0.0.1"), AddrClass::Multicast);
+        assert_eq!(classify("ff02::1"), AddrClass::Multicast);
+
+        assert_eq!(classify("10.1.2.3"), AddrClass::Private);
+        assert_eq!(classify("192.168.0.1"), AddrClass::Private);
+        assert_eq!(classify("169.254.0.1"), AddrClass::Private);
+        assert_eq!(classify("fd00::1"), AddrClass::Private);
+        assert_eq!(classify("fe80::1"), AddrClass::Private);
+
+        assert_eq!(classify("8.8.8.8"), AddrClass::Global);
+        assert_eq!(classify("2001:4860:4860::8888"), AddrClass::Global);
     }
 
     #[test]
@@ -705,6 +984,88 @@ mod tests {
         parse_host_port("127.0.0.0").unwrap_err();
     }
 
+    #[test]
+    fn test_resolve_cache() {
+        let ttl = Duration::from_secs(60);
+        let cache = ResolveCache::new(ttl);
+        let resolved_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let num_resolves = std::cell::Cell::new(0);
+        let resolve = |host_port: &str| {
+            num_resolves.set(num_resolves.get() + 1);
+            let port = host_port.rsplit_once(':').unwrap().1.parse().unwrap();
+            Ok(SocketAddr::new(resolved_ip, port))
+        };
+        let start = Instant::now();
+
+        assert_eq!(
+            cache.resolve("entrypoint.test:8001", start, resolve),
+            Ok(SocketAddr::new(resolved_ip, 8001))
+        );
+        assert_eq!(num_resolves.get(), 1);
+
+        // Another port on the same host within the TTL is a cache hit
+        assert_eq!(
+            cache.resolve("entrypoint.test:8002", start + ttl / 2, resolve),
+            Ok(SocketAddr::new(resolved_ip, 8002))
+        );
+        assert_eq!(num_resolves.get(), 1);
+
+        // The entry has expired
+        assert_eq!(
+            cache.resolve("entrypoint.test:8001", start + ttl, resolve),
+            Ok(SocketAddr::new(resolved_ip, 8001))
+        );
+        assert_eq!(num_resolves.get(), 2);
+
+        // Failures aren't cached
+        let fail = |host_port: &str| Err(format!("Unable to resolve host {host_port}"));
+        cache.resolve("other.test:8001", start, fail).unwrap_err();
+        cache.resolve("other.test:8001", start, fail).unwrap_err();
+        cache.resolve("other.test", start, resolve).unwrap_err();
+        assert_eq!(num_resolves.get(), 2);
+    }
+
+    #[test]
+    fn test_parse_host_port_cached() {
+        assert_eq!(
+            parse_host_port_cached("127.0.0.1:1234"),
+            Ok(SocketAddr::from(([127, 0, 0, 1], 1234)))
+        );
+        assert_eq!(
+            parse_host_port_cached("127.0.0.1:4321"),
+            Ok(SocketAddr::from(([127, 0, 0, 1], 4321)))
+        );
+        parse_host_port_cached("127.0.0.1").unwrap_err();
+    }
+
+    #[test]
+    fn test_parse_host_port_with_timeout() {
+        assert_eq!(
+            parse_host_port_with_timeout("127.0.0.1:1234", Duration::from_secs(5)),
+            Ok(SocketAddr::from(([127, 0, 0, 1], 1234)))
+        );
+        parse_host_port_with_timeout("localhost:1234", Duration::from_secs(5)).unwrap();
+        parse_host_port_with_timeout("localhost", Duration::from_secs(5)).unwrap_err();
+    }
+
+    #[test]
+    fn test_resolve_with_timeout() {
+        assert_eq!(
+            resolve_with_timeout(|| 42, Duration::from_secs(5)).unwrap(),
+            Some(42)
+        );
+
+        // a resolver stuck on an unresponsive (blackhole) DNS server
+        let blackhole = || {
+            thread::sleep(Duration::from_secs(5));
+            resolve_host_port("blackhole.invalid:1234")
+        };
+        assert_eq!(
+            resolve_with_timeout(blackhole, Duration::from_millis(10)).unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_is_host_port() {
         assert!(is_host_port("localhost:1234".to_string()).is_ok());
@@ -746,6 +1107,24 @@ mod tests {
         assert_eq!(res.len(), 16, "Should reserve 16 unique ports");
     }
 
+    #[test]
+    fn test_allocate_validator_ports() {
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let range = unique_port_range_for_tests(6);
+        // reserve 1 port to make it non-trivial
+        let _sock = sockets::bind_to(ip_addr, range.start + 1).unwrap();
+        let ports = allocate_validator_ports(ip_addr, (range.start, range.end), 5).unwrap();
+        assert_eq!(ports.len(), 5);
+        assert!(ports.iter().map(|(port, _)| port).all_unique());
+        for (port, socket) in &ports {
+            assert!(range.contains(port));
+            assert_ne!(*port, range.start + 1);
+            assert_eq!(socket.local_addr().unwrap().port(), *port);
+        }
+        // the range is exhausted while the sockets are held
+        allocate_validator_ports(ip_addr, (range.start, range.end), 1).unwrap_err();
+    }
+
     #[allow(deprecated)]
     #[test]
     fn test_multi_bind_in_range_with_config_reuseport_disabled() {
@@ -1,4 +1,5 @@
 #![cfg(feature = "agave-unstable-api")]
+use crate::{classify_addr, AddrClass};
 use std::{
     net::{IpAddr, Ipv4Addr, UdpSocket},
     ops::Deref,
@@ -95,7 +96,10 @@ impl BindIpAddrs {
         }
         if addrs.len() > 1 {
             for ip in &addrs {
-                if ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() {
+                if matches!(
+                    classify_addr(*ip),
+                    AddrClass::Loopback | AddrClass::Unspecified | AddrClass::Multicast
+                ) {
                     return Err(format!(
                         "Invalid configuration: {:?} is not allowed with multiple --bind-address values (loopback, unspecified, or multicast)",
                         ip
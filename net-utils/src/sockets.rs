@@ -5,10 +5,14 @@ use {
     log::warn,
     socket2::{Domain, SockAddr, Socket, Type},
     std::{
+        collections::HashSet,
         io,
         net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, UdpSocket},
         ops::Range,
-        sync::atomic::{AtomicU16, Ordering},
+        sync::{
+            atomic::{AtomicU16, Ordering},
+            Mutex, OnceLock,
+        },
     },
 };
 // base port for deconflicted allocations
@@ -66,6 +70,80 @@ pub fn bind_to_localhost_unique() -> io::Result<UdpSocket> {
     )
 }
 
+/// A process-global allocator of ports for test harnesses that spin up many
+/// nodes from several threads.
+///
+/// [`find_available_ports_in_range`](crate::find_available_ports_in_range) only
+/// checks that a port is free at the time of the call, so two threads may be
+/// handed the same port. The allocator instead tracks every port it hands out
+/// and keeps it reserved, by holding sockets bound to it, until the returned
+/// [`PortReservation`] is dropped.
+#[derive(Debug, Default)]
+pub struct PortAllocator {
+    allocated: Mutex<HashSet<u16>>,
+}
+
+impl PortAllocator {
+    /// The allocator shared by the whole process.
+    pub fn global() -> &'static PortAllocator {
+        static PORT_ALLOCATOR: OnceLock<PortAllocator> = OnceLock::new();
+        PORT_ALLOCATOR.get_or_init(PortAllocator::default)
+    }
+
+    /// Reserves a port in `range` that is neither handed out by this allocator
+    /// nor bound by anyone else.
+    pub fn allocate(&self, ip_addr: IpAddr, range: PortRange) -> io::Result<PortReservation<'_>> {
+        let mut allocated = self.allocated.lock().unwrap();
+        let config = SocketConfiguration::default();
+        for port in range.0..range.1 {
+            if allocated.contains(&port) {
+                continue;
+            }
+            if let Ok(sockets) = bind_common_with_config(ip_addr, port, config) {
+                allocated.insert(port);
+                return Ok(PortReservation {
+                    port,
+                    sockets,
+                    allocator: self,
+                });
+            }
+        }
+        Err(io::Error::other(format!(
+            "no available port in range {range:?}"
+        )))
+    }
+
+    fn release(&self, port: u16) {
+        self.allocated.lock().unwrap().remove(&port);
+    }
+}
+
+/// A port handed out by a [`PortAllocator`]; the port is released back to the
+/// allocator when this is dropped.
+#[derive(Debug)]
+pub struct PortReservation<'a> {
+    port: u16,
+    sockets: (UdpSocket, TcpListener),
+    allocator: &'a PortAllocator,
+}
+
+impl PortReservation<'_> {
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// The UDP socket and TCP listener bound to the reserved port.
+    pub fn sockets(&self) -> &(UdpSocket, TcpListener) {
+        &self.sockets
+    }
+}
+
+impl Drop for PortReservation<'_> {
+    fn drop(&mut self) {
+        self.allocator.release(self.port);
+    }
+}
+
 pub fn bind_gossip_port_in_range(
     gossip_addr: &SocketAddr,
     port_range: PortRange,
@@ -178,6 +256,40 @@ pub(crate) fn udp_socket_with_config(config: SocketConfiguration) -> io::Result<
     Ok(sock)
 }
 
+/// Binds a `TcpListener` to `addr`, applying the same socket configuration
+/// as [`udp_socket_with_config`] does for UDP sockets.
+pub(crate) fn tcp_listener_with_config(
+    addr: SocketAddr,
+    config: SocketConfiguration,
+) -> io::Result<TcpListener> {
+    let SocketConfiguration {
+        reuseport,
+        recv_buffer_size,
+        send_buffer_size,
+        non_blocking,
+    } = config;
+    let sock = Socket::new(Domain::for_address(addr), Type::STREAM, None)?;
+    // Match `TcpListener::bind`, which sets SO_REUSEADDR on unix platforms
+    #[cfg(not(windows))]
+    sock.set_reuse_address(true)?;
+    if PLATFORM_SUPPORTS_SOCKET_CONFIGS {
+        if let Some(recv_buffer_size) = recv_buffer_size {
+            sock.set_recv_buffer_size(recv_buffer_size)?;
+        }
+        if let Some(send_buffer_size) = send_buffer_size {
+            sock.set_send_buffer_size(send_buffer_size)?;
+        }
+
+        if reuseport {
+            set_reuse_port(&sock)?;
+        }
+    }
+    sock.set_nonblocking(non_blocking)?;
+    sock.bind(&SockAddr::from(addr))?;
+    sock.listen(128)?;
+    Ok(sock.into())
+}
+
 /// Find a port in the given range with a socket config that is available for both TCP and UDP
 pub fn bind_common_in_range_with_config(
     ip_addr: IpAddr,
@@ -301,8 +413,9 @@ pub fn bind_common_with_config(
 
     let addr = SocketAddr::new(ip_addr, port);
     let sock_addr = SockAddr::from(addr);
-    sock.bind(&sock_addr)
-        .and_then(|_| TcpListener::bind(addr).map(|listener| (sock.into(), listener)))
+    sock.bind(&sock_addr).and_then(|_| {
+        tcp_listener_with_config(addr, config).map(|listener| (sock.into(), listener))
+    })
 }
 
 pub fn bind_two_in_range_with_offset_and_config(
@@ -408,6 +521,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_port_allocator_no_duplicates() {
+        const NUM_THREADS: usize = 8;
+        const PORTS_PER_THREAD: usize = 10;
+        let port_range =
+            unique_port_range_for_tests(NUM_THREADS as u16 * PORTS_PER_THREAD as u16 * 2);
+        let port_range = (port_range.start, port_range.end);
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let reservations: Vec<_> = (0..NUM_THREADS)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    (0..PORTS_PER_THREAD)
+                        .map(|_| {
+                            PortAllocator::global()
+                                .allocate(ip_addr, port_range)
+                                .unwrap()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        let ports: HashSet<_> = reservations.iter().map(PortReservation::port).collect();
+        assert_eq!(ports.len(), NUM_THREADS * PORTS_PER_THREAD);
+        for reservation in &reservations {
+            assert_eq!(
+                reservation.sockets().0.local_addr().unwrap().port(),
+                reservation.port()
+            );
+        }
+    }
+
+    #[test]
+    fn test_port_allocator_release() {
+        let port_range = unique_port_range_for_tests(1);
+        let port_range = (port_range.start, port_range.end);
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let allocator = PortAllocator::default();
+
+        let reservation = allocator.allocate(ip_addr, port_range).unwrap();
+        assert_eq!(reservation.port(), port_range.0);
+        allocator.allocate(ip_addr, port_range).unwrap_err();
+
+        drop(reservation);
+        let reservation = allocator.allocate(ip_addr, port_range).unwrap();
+        assert_eq!(reservation.port(), port_range.0);
+    }
+
     #[test]
     fn test_bind_with_any_port() {
         let x = bind_to_localhost_unique().unwrap();
@@ -448,6 +612,26 @@ mod tests {
         bind_common_in_range_with_config(ip_addr, (port, port + 1), config).unwrap_err();
     }
 
+    #[test]
+    fn test_bind_common_with_reuseport() {
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let range = unique_port_range_for_tests(1);
+        let config = SocketConfiguration {
+            reuseport: true,
+            ..SocketConfiguration::default()
+        };
+        let (udp1, tcp1) = bind_common_with_config(ip_addr, range.start, config).unwrap();
+        let port = udp1.local_addr().unwrap().port();
+        assert_eq!(tcp1.local_addr().unwrap().port(), port);
+        if PLATFORM_SUPPORTS_SOCKET_CONFIGS {
+            let (udp2, tcp2) = bind_common_with_config(ip_addr, port, config).unwrap();
+            assert_eq!(udp2.local_addr().unwrap().port(), port);
+            assert_eq!(tcp2.local_addr().unwrap().port(), port);
+        }
+        // Without reuseport the port is still taken
+        bind_common_with_config(ip_addr, port, SocketConfiguration::default()).unwrap_err();
+    }
+
     #[test]
     fn test_bind_two_in_range_with_offset() {
         solana_logger::setup();
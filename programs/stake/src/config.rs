@@ -1,8 +1,10 @@
-use bincode::serialize;
+use bincode::{deserialize, serialize};
 use solana_account::{Account, AccountSharedData, ReadableAccount, WritableAccount};
-use solana_config_interface::state::ConfigKeys;
+use solana_config_interface::state::{get_config_data, ConfigKeys};
 use solana_genesis_config::GenesisConfig;
+use solana_instruction_error::InstructionError;
 use solana_pubkey::Pubkey;
+#[allow(deprecated)]
 use solana_stake_interface::config::Config;
 
 #[allow(deprecated)]
@@ -17,6 +19,18 @@ pub fn add_genesis_account(genesis_config: &mut GenesisConfig) -> u64 {
     lamports
 }
 
+/// Reads the stake [`Config`] back out of a config account, skipping the
+/// [`ConfigKeys`] prefix written by `create_config_account`.
+#[allow(deprecated)]
+pub fn get_config(account: &AccountSharedData) -> Result<Config, InstructionError> {
+    if account.owner() != &solana_sdk_ids::config::id() {
+        return Err(InstructionError::InvalidAccountOwner);
+    }
+    get_config_data(account.data())
+        .and_then(deserialize)
+        .map_err(|_| InstructionError::InvalidAccountData)
+}
+
 #[allow(deprecated)]
 fn create_config_account(
     keys: Vec<(Pubkey, bool)>,
@@ -32,3 +46,43 @@ fn create_config_account(
         ..Account::default()
     })
 }
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_config_round_trip() {
+        let mut genesis_config = GenesisConfig::default();
+        add_genesis_account(&mut genesis_config);
+
+        let account = AccountSharedData::from(
+            genesis_config.accounts[&solana_stake_interface::config::id()].clone(),
+        );
+        assert_eq!(get_config(&account), Ok(Config::default()));
+
+        let config = Config {
+            warmup_cooldown_rate: 0.5,
+            slash_penalty: 10,
+        };
+        let account = create_config_account(vec![(Pubkey::new_unique(), true)], &config, 1);
+        assert_eq!(get_config(&account), Ok(config));
+    }
+
+    #[test]
+    fn test_get_config_invalid_account() {
+        let mut account = create_config_account(vec![], &Config::default(), 1);
+        account.set_owner(Pubkey::new_unique());
+        assert_eq!(
+            get_config(&account),
+            Err(InstructionError::InvalidAccountOwner)
+        );
+
+        let account = AccountSharedData::new(1, 0, &solana_sdk_ids::config::id());
+        assert_eq!(
+            get_config(&account),
+            Err(InstructionError::InvalidAccountData)
+        );
+    }
+}
@@ -3,6 +3,8 @@ use solana_genesis_config::GenesisConfig;
 pub mod stake_state;
 pub mod config;
 mod epoch_rewards;
+pub mod points;
+pub mod rewards;
 
 pub fn add_genesis_accounts(genesis_config: &mut GenesisConfig) -> u64 {
     let config_lamports = config::add_genesis_account(genesis_config);
@@ -0,0 +1,144 @@
+//! Information about points calculation based on stake state.
+use std::cmp::Ordering;
+
+use solana_stake_interface::stake_history::StakeHistory;
+use solana_stake_interface::state::Stake;
+use solana_vote_interface::state::VoteStateV3;
+
+/// captures a rewards round as lamports to be awarded
+///  and the total points over which those lamports
+///  are to be distributed
+//  basically read as rewards/points, but in integers instead of as an f64
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointValue {
+    pub rewards: u64, // lamports to split
+    pub points: u128, // over these points
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CalculatedStakePoints {
+    pub(crate) points: u128,
+    pub(crate) new_credits_observed: u64,
+    pub(crate) force_credits_update_with_skipped_reward: bool,
+}
+
+/// Calculates the points earned by a stake: its effective stake multiplied by
+/// the vote credits earned in each epoch since `credits_observed`.
+pub fn calculate_points(
+    stake: &Stake,
+    vote_state: &VoteStateV3,
+    stake_history: &StakeHistory,
+) -> u128 {
+    calculate_stake_points_and_credits(stake, vote_state, stake_history).points
+}
+
+/// for a given stake and vote_state, calculate how many
+///   points were earned (credits * stake) and new value
+///   for credits_observed were the points paid
+pub(crate) fn calculate_stake_points_and_credits(
+    stake: &Stake,
+    vote_state: &VoteStateV3,
+    stake_history: &StakeHistory,
+) -> CalculatedStakePoints {
+    let credits_in_stake = stake.credits_observed;
+    let credits_in_vote = vote_state.credits();
+    // if there is no newer credits since observed, return no point
+    match credits_in_vote.cmp(&credits_in_stake) {
+        Ordering::Less => {
+            // the vote account was probably recreated, so reset credits_observed
+            return CalculatedStakePoints {
+                points: 0,
+                new_credits_observed: credits_in_vote,
+                force_credits_update_with_skipped_reward: true,
+            };
+        }
+        Ordering::Equal => {
+            return CalculatedStakePoints {
+                points: 0,
+                new_credits_observed: credits_in_stake,
+                force_credits_update_with_skipped_reward: false,
+            };
+        }
+        Ordering::Greater => {}
+    }
+
+    let mut points = 0;
+    let mut new_credits_observed = credits_in_stake;
+
+    for (epoch, final_epoch_credits, initial_epoch_credits) in
+        vote_state.epoch_credits().iter().copied()
+    {
+        let stake_amount = u128::from(stake.delegation.stake(epoch, stake_history, None));
+
+        // figure out how much this stake has seen that
+        //   for which the vote account has a record
+        let earned_credits = if credits_in_stake < initial_epoch_credits {
+            // the staker observed the entire epoch
+            final_epoch_credits - initial_epoch_credits
+        } else if credits_in_stake < final_epoch_credits {
+            // the staker registered sometime during the epoch, partial credit
+            final_epoch_credits - new_credits_observed
+        } else {
+            // the staker has already observed or been redeemed this epoch
+            //  or was activated after this epoch
+            0
+        };
+        let earned_credits = u128::from(earned_credits);
+
+        // don't want to assume anything about order of the iterator...
+        new_credits_observed = new_credits_observed.max(final_epoch_credits);
+
+        // finally calculate points for this epoch
+        points += stake_amount * earned_credits;
+    }
+
+    CalculatedStakePoints {
+        points,
+        new_credits_observed,
+        force_credits_update_with_skipped_reward: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_pubkey::Pubkey;
+    use solana_stake_interface::stake_history::Epoch;
+    use solana_stake_interface::state::Delegation;
+
+    fn new_stake(stake: u64, activation_epoch: Epoch) -> Stake {
+        Stake {
+            delegation: Delegation::new(&Pubkey::new_unique(), stake, activation_epoch),
+            credits_observed: 0,
+        }
+    }
+
+    #[test]
+    fn test_calculate_points_proportional_to_stake() {
+        let mut vote_state = VoteStateV3::default();
+        vote_state.increment_credits(0, 10);
+        vote_state.increment_credits(1, 20);
+        let stake_history = StakeHistory::default();
+
+        let small = new_stake(1_000, Epoch::MAX);
+        let large = new_stake(3_000, Epoch::MAX);
+
+        let small_points = calculate_points(&small, &vote_state, &stake_history);
+        let large_points = calculate_points(&large, &vote_state, &stake_history);
+        assert_eq!(small_points, 1_000 * 30);
+        assert_eq!(large_points, 3 * small_points);
+    }
+
+    #[test]
+    fn test_calculate_points_no_new_credits() {
+        let mut vote_state = VoteStateV3::default();
+        vote_state.increment_credits(0, 10);
+        let mut stake = new_stake(1_000, Epoch::MAX);
+        stake.credits_observed = vote_state.credits();
+
+        assert_eq!(
+            calculate_points(&stake, &vote_state, &StakeHistory::default()),
+            0
+        );
+    }
+}
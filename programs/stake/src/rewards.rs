@@ -0,0 +1,223 @@
+//! Information about stake and voter rewards based on stake state.
+use solana_stake_interface::stake_history::StakeHistory;
+use solana_stake_interface::state::Stake;
+use solana_vote_interface::state::VoteStateV3;
+
+use crate::points::{calculate_stake_points_and_credits, CalculatedStakePoints, PointValue};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CalculatedStakeRewards {
+    pub staker_rewards: u64,
+    pub voter_rewards: u64,
+    pub new_credits_observed: u64,
+}
+
+/// for a given stake and vote_state, calculate what distributions and what updates should be made
+/// returns a tuple in the case of a payout of:
+///   * staker_rewards to be distributed
+///   * voter_rewards to be distributed
+///   * new value for credits_observed in the stake
+///
+/// returns None if there's no payout or if any deserved payout is < 1 lamport
+pub fn calculate_stake_rewards(
+    stake: &Stake,
+    vote_state: &VoteStateV3,
+    point_value: PointValue,
+    stake_history: &StakeHistory,
+) -> Option<CalculatedStakeRewards> {
+    let CalculatedStakePoints {
+        points,
+        new_credits_observed,
+        mut force_credits_update_with_skipped_reward,
+    } = calculate_stake_points_and_credits(stake, vote_state, stake_history);
+
+    // Drive credits_observed forward unconditionally when rewards are disabled
+    if point_value.rewards == 0 {
+        force_credits_update_with_skipped_reward = true;
+    }
+
+    if force_credits_update_with_skipped_reward {
+        return Some(CalculatedStakeRewards {
+            staker_rewards: 0,
+            voter_rewards: 0,
+            new_credits_observed,
+        });
+    }
+
+    if points == 0 || point_value.points == 0 {
+        return None;
+    }
+
+    let rewards = points
+        .checked_mul(u128::from(point_value.rewards))
+        .expect("Rewards intermediate calculation should fit within u128")
+        .checked_div(point_value.points)
+        .unwrap();
+
+    let rewards = u64::try_from(rewards).expect("Rewards should fit within u64");
+
+    // don't bother trying to split if fractional lamports got truncated
+    if rewards == 0 {
+        return None;
+    }
+    let (voter_rewards, staker_rewards, is_split) =
+        commission_split(vote_state.commission, rewards);
+    if (voter_rewards == 0 || staker_rewards == 0) && is_split {
+        // don't collect if we lose a whole lamport somewhere
+        //  is_split means there should be tokens on both sides,
+        //  uncool to move credits_observed if one side didn't get paid
+        return None;
+    }
+
+    Some(CalculatedStakeRewards {
+        staker_rewards,
+        voter_rewards,
+        new_credits_observed,
+    })
+}
+
+/// returns commission split as (voter_portion, staker_portion, was_split) tuple
+///
+///  if commission calculation is 100% one way or other,
+///   indicate with false for was_split
+fn commission_split(commission: u8, on: u64) -> (u64, u64, bool) {
+    match commission.min(100) {
+        0 => (0, on, false),
+        100 => (on, 0, false),
+        split => {
+            let on = u128::from(on);
+            // Calculate mine and theirs independently and symmetrically instead of
+            // using the remainder of the other to treat them strictly equally.
+            // This is also to cancel the rewarding if either of the parties
+            // should receive only fractional lamports, resulting in not being rewarded at all.
+            // Thus, note that we intentionally discard any residual fractional lamports.
+            let mine = on * u128::from(split) / 100u128;
+            let theirs = on * u128::from(100 - split) / 100u128;
+
+            (mine as u64, theirs as u64, true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_pubkey::Pubkey;
+    use solana_stake_interface::stake_history::Epoch;
+    use solana_stake_interface::state::Delegation;
+
+    fn new_stake(stake: u64, credits_observed: u64) -> Stake {
+        Stake {
+            delegation: Delegation::new(&Pubkey::new_unique(), stake, Epoch::MAX),
+            credits_observed,
+        }
+    }
+
+    #[test]
+    fn test_calculate_stake_rewards_earned_credits() {
+        let mut vote_state = VoteStateV3::default();
+        vote_state.increment_credits(0, 1);
+        let stake = new_stake(1_000, 0);
+
+        assert_eq!(
+            calculate_stake_rewards(
+                &stake,
+                &vote_state,
+                PointValue {
+                    rewards: 1_000_000,
+                    points: 1_000,
+                },
+                &StakeHistory::default(),
+            ),
+            Some(CalculatedStakeRewards {
+                staker_rewards: 1_000_000,
+                voter_rewards: 0,
+                new_credits_observed: 1,
+            })
+        );
+
+        // half of the points in the round belong to this stake
+        vote_state.commission = 10;
+        assert_eq!(
+            calculate_stake_rewards(
+                &stake,
+                &vote_state,
+                PointValue {
+                    rewards: 1_000_000,
+                    points: 2_000,
+                },
+                &StakeHistory::default(),
+            ),
+            Some(CalculatedStakeRewards {
+                staker_rewards: 450_000,
+                voter_rewards: 50_000,
+                new_credits_observed: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_calculate_stake_rewards_no_credits() {
+        let mut vote_state = VoteStateV3::default();
+        vote_state.increment_credits(0, 3);
+        // stake has already observed every credit of the vote account
+        let stake = new_stake(1_000, vote_state.credits());
+
+        assert_eq!(
+            calculate_stake_rewards(
+                &stake,
+                &vote_state,
+                PointValue {
+                    rewards: 1_000_000,
+                    points: 1_000,
+                },
+                &StakeHistory::default(),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_calculate_stake_rewards_credits_observed() {
+        let mut vote_state = VoteStateV3::default();
+        vote_state.increment_credits(0, 2);
+        vote_state.increment_credits(1, 3);
+        // stake observed 1 credit of epoch 0, so it earns 1 + 3 more
+        let stake = new_stake(1_000, 1);
+
+        assert_eq!(
+            calculate_stake_rewards(
+                &stake,
+                &vote_state,
+                PointValue {
+                    rewards: 4_000,
+                    points: 4_000,
+                },
+                &StakeHistory::default(),
+            ),
+            Some(CalculatedStakeRewards {
+                staker_rewards: 4_000,
+                voter_rewards: 0,
+                new_credits_observed: 5,
+            })
+        );
+
+        // rewards disabled still drives credits_observed forward
+        assert_eq!(
+            calculate_stake_rewards(
+                &stake,
+                &vote_state,
+                PointValue {
+                    rewards: 0,
+                    points: 4_000,
+                },
+                &StakeHistory::default(),
+            ),
+            Some(CalculatedStakeRewards {
+                staker_rewards: 0,
+                voter_rewards: 0,
+                new_credits_observed: 5,
+            })
+        );
+    }
+}
@@ -1,10 +1,14 @@
 use solana_account::{state_traits::StateMut, AccountSharedData, ReadableAccount};
+use solana_clock::Clock;
 use solana_pubkey::Pubkey;
 use solana_rent::Rent;
 use solana_sdk_ids::stake::id;
+use solana_stake_interface::error::StakeError;
 use solana_stake_interface::stake_flags::StakeFlags;
-use solana_stake_interface::stake_history::Epoch;
-use solana_stake_interface::state::{Authorized, Delegation, Meta, Stake, StakeStateV2};
+use solana_stake_interface::stake_history::{Epoch, StakeHistory};
+use solana_stake_interface::state::{
+    Authorized, Delegation, Meta, Stake, StakeAuthorize, StakeStateV2,
+};
 use solana_vote_interface::state::VoteStateV3;
 
 pub fn create_account(
@@ -69,3 +73,539 @@ fn new_stake(
         credits_observed: vote_state.credits(),
     }
 }
+
+/// Sets the staker or withdrawer of `meta` to `new_authority`.
+///
+/// The withdrawer can't be changed while the lockup is in force, unless the
+/// lockup `custodian` is given. Checking that the current authority and the
+/// custodian signed is left to the caller.
+pub fn authorize(
+    meta: &mut Meta,
+    new_authority: Pubkey,
+    authorize_type: StakeAuthorize,
+    clock: &Clock,
+    custodian: Option<&Pubkey>,
+) -> Result<(), StakeError> {
+    match authorize_type {
+        StakeAuthorize::Staker => meta.authorized.staker = new_authority,
+        StakeAuthorize::Withdrawer => {
+            if meta.lockup.is_in_force(clock, None) {
+                let Some(custodian) = custodian else {
+                    return Err(StakeError::CustodianMissing);
+                };
+                if meta.lockup.is_in_force(clock, Some(custodian)) {
+                    return Err(StakeError::LockupInForce);
+                }
+            }
+            meta.authorized.withdrawer = new_authority;
+        }
+    }
+    Ok(())
+}
+
+/// Withdraws `lamports` from a stake account holding `current_balance` and
+/// returns the remaining balance.
+///
+/// Withdrawals are rejected while the lockup is in force, unless the lockup
+/// `custodian` is given, and may not dip into the rent exempt reserve or the
+/// stake of a delegation that is still active or cooling down, as recorded in
+/// `stake_history`. A stake account with no active stake may be drained
+/// entirely. Checking that the withdrawer and the
+/// custodian signed is left to the caller.
+pub fn withdraw(
+    stake_state: &StakeStateV2,
+    lamports: u64,
+    current_balance: u64,
+    rent_exempt_reserve: u64,
+    clock: &Clock,
+    stake_history: &StakeHistory,
+    custodian: Option<&Pubkey>,
+) -> Result<u64, StakeError> {
+    let (lockup, reserve, is_staked) = match stake_state {
+        StakeStateV2::Stake(meta, stake, _stake_flags) => {
+            let staked = if clock.epoch >= stake.delegation.deactivation_epoch {
+                // Deactivated stake cools down over several epochs
+                stake.delegation.stake(clock.epoch, stake_history, None)
+            } else {
+                // The full stake of an active delegation is locked, as the
+                // effective stake may still grow while warming up
+                stake.delegation.stake
+            };
+            (
+                Some(meta.lockup),
+                staked.saturating_add(rent_exempt_reserve),
+                staked != 0,
+            )
+        }
+        StakeStateV2::Initialized(meta) => (Some(meta.lockup), rent_exempt_reserve, false),
+        StakeStateV2::Uninitialized => (None, 0, false),
+        // Rewards pools are deprecated and hold nothing withdrawable
+        StakeStateV2::RewardsPool => return Err(StakeError::InsufficientStake),
+    };
+
+    if lockup.is_some_and(|lockup| lockup.is_in_force(clock, custodian)) {
+        return Err(if custodian.is_none() {
+            StakeError::CustodianMissing
+        } else {
+            StakeError::LockupInForce
+        });
+    }
+
+    let remaining = current_balance
+        .checked_sub(lamports)
+        .ok_or(StakeError::InsufficientStake)?;
+    if remaining == 0 {
+        // Draining the account is only allowed once nothing is staked
+        if is_staked {
+            return Err(StakeError::InsufficientStake);
+        }
+    } else if remaining < reserve {
+        return Err(StakeError::InsufficientStake);
+    }
+    Ok(remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_account::WritableAccount;
+    use solana_stake_interface::stake_history::StakeHistoryEntry;
+    use solana_stake_interface::state::Lockup;
+    use solana_vote_interface::state::VoteStateVersions;
+
+    fn new_vote_account() -> AccountSharedData {
+        let mut vote_account =
+            AccountSharedData::new(1, VoteStateV3::size_of(), &solana_sdk_ids::vote::id());
+        VoteStateV3::serialize(
+            &VoteStateVersions::V3(Box::default()),
+            vote_account.data_as_mut_slice(),
+        )
+        .unwrap();
+        vote_account
+    }
+
+    fn delegation_of(stake_account: &AccountSharedData) -> Delegation {
+        match stake_account.state().unwrap() {
+            StakeStateV2::Stake(_, stake, _) => stake.delegation,
+            state => panic!("unexpected stake state: {state:?}"),
+        }
+    }
+
+    #[test]
+    fn test_create_account_is_bootstrap() {
+        let rent = Rent::default();
+        let lamports = rent.minimum_balance(StakeStateV2::size_of()) + 1_000_000;
+        let stake_account = create_account(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &new_vote_account(),
+            &rent,
+            lamports,
+        );
+
+        let delegation = delegation_of(&stake_account);
+        assert!(delegation.is_bootstrap());
+        assert_eq!(
+            delegation.stake(0, &StakeHistory::default(), None),
+            1_000_000
+        );
+    }
+
+    #[test]
+    fn test_non_bootstrap_delegation_warms_up() {
+        let rent = Rent::default();
+        let lamports = rent.minimum_balance(StakeStateV2::size_of()) + 1_000_000;
+        let stake_account = do_create_account(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &new_vote_account(),
+            &rent,
+            lamports,
+            0,
+        );
+
+        let delegation = delegation_of(&stake_account);
+        assert!(!delegation.is_bootstrap());
+        let stake_history = StakeHistory::default();
+        assert_eq!(delegation.stake(0, &stake_history, None), 0);
+        // Without any recorded history the warmup completes in the next epoch.
+        assert_eq!(delegation.stake(1, &stake_history, None), 1_000_000);
+    }
+
+    #[test]
+    fn test_authorize_staker() {
+        let authority = Pubkey::new_unique();
+        let mut meta = Meta {
+            authorized: Authorized::auto(&authority),
+            ..Meta::default()
+        };
+        let new_staker = Pubkey::new_unique();
+        authorize(
+            &mut meta,
+            new_staker,
+            StakeAuthorize::Staker,
+            &Clock::default(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(meta.authorized.staker, new_staker);
+        assert_eq!(meta.authorized.withdrawer, authority);
+    }
+
+    #[test]
+    fn test_authorize_withdrawer_with_lockup() {
+        let authority = Pubkey::new_unique();
+        let custodian = Pubkey::new_unique();
+        let mut meta = Meta {
+            authorized: Authorized::auto(&authority),
+            lockup: Lockup {
+                epoch: 10,
+                custodian,
+                ..Lockup::default()
+            },
+            ..Meta::default()
+        };
+        let new_withdrawer = Pubkey::new_unique();
+        let clock = Clock {
+            epoch: 5,
+            ..Clock::default()
+        };
+
+        assert_eq!(
+            authorize(
+                &mut meta,
+                new_withdrawer,
+                StakeAuthorize::Withdrawer,
+                &clock,
+                None
+            ),
+            Err(StakeError::CustodianMissing)
+        );
+        assert_eq!(
+            authorize(
+                &mut meta,
+                new_withdrawer,
+                StakeAuthorize::Withdrawer,
+                &clock,
+                Some(&Pubkey::new_unique())
+            ),
+            Err(StakeError::LockupInForce)
+        );
+        assert_eq!(meta.authorized.withdrawer, authority);
+
+        // The staker isn't subject to the lockup
+        authorize(
+            &mut meta,
+            new_withdrawer,
+            StakeAuthorize::Staker,
+            &clock,
+            None,
+        )
+        .unwrap();
+
+        authorize(
+            &mut meta,
+            new_withdrawer,
+            StakeAuthorize::Withdrawer,
+            &clock,
+            Some(&custodian),
+        )
+        .unwrap();
+        assert_eq!(meta.authorized.withdrawer, new_withdrawer);
+
+        // Once the lockup has expired no custodian is needed
+        let clock = Clock {
+            epoch: 10,
+            ..Clock::default()
+        };
+        authorize(
+            &mut meta,
+            authority,
+            StakeAuthorize::Withdrawer,
+            &clock,
+            None,
+        )
+        .unwrap();
+        assert_eq!(meta.authorized.withdrawer, authority);
+    }
+
+    fn new_delegated_stake_state(
+        stake: u64,
+        deactivation_epoch: Epoch,
+        lockup: Lockup,
+    ) -> StakeStateV2 {
+        StakeStateV2::Stake(
+            Meta {
+                lockup,
+                ..Meta::default()
+            },
+            Stake {
+                delegation: Delegation {
+                    stake,
+                    deactivation_epoch,
+                    ..Delegation::new(&Pubkey::new_unique(), stake, 0)
+                },
+                credits_observed: 0,
+            },
+            StakeFlags::empty(),
+        )
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let clock = Clock {
+            epoch: 5,
+            ..Clock::default()
+        };
+        // Without history, deactivated stake cools down within one epoch
+        let stake_history = StakeHistory::default();
+        let stake_state = new_delegated_stake_state(1_000, Epoch::MAX, Lockup::default());
+        // Only the lamports above the stake and the reserve can be withdrawn
+        assert_eq!(
+            withdraw(&stake_state, 400, 1_500, 100, &clock, &stake_history, None),
+            Ok(1_100)
+        );
+        assert_eq!(
+            withdraw(&stake_state, 401, 1_500, 100, &clock, &stake_history, None),
+            Err(StakeError::InsufficientStake)
+        );
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Err(StakeError::InsufficientStake)
+        );
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_501,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Err(StakeError::InsufficientStake)
+        );
+
+        // A stake deactivated in a past epoch can be drained
+        let stake_state = new_delegated_stake_state(1_000, 4, Lockup::default());
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Ok(0)
+        );
+        // but a partial withdrawal still has to leave the reserve
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_401,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Err(StakeError::InsufficientStake)
+        );
+        // The stake is still cooling down in its deactivation epoch
+        let stake_state = new_delegated_stake_state(1_000, 5, Lockup::default());
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Err(StakeError::InsufficientStake)
+        );
+
+        assert_eq!(
+            withdraw(
+                &StakeStateV2::Uninitialized,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_withdraw_with_lockup() {
+        let custodian = Pubkey::new_unique();
+        let lockup = Lockup {
+            epoch: 10,
+            custodian,
+            ..Lockup::default()
+        };
+        let clock = Clock {
+            epoch: 5,
+            ..Clock::default()
+        };
+        let stake_state = new_delegated_stake_state(1_000, 4, lockup);
+        let stake_history = StakeHistory::default();
+
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Err(StakeError::CustodianMissing)
+        );
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                Some(&Pubkey::new_unique())
+            ),
+            Err(StakeError::LockupInForce)
+        );
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                Some(&custodian)
+            ),
+            Ok(0)
+        );
+
+        let clock = Clock {
+            epoch: 10,
+            ..Clock::default()
+        };
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock,
+                &stake_history,
+                None
+            ),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_withdraw_during_cooldown() {
+        // The stake is the only one deactivating in the cluster, so it cools
+        // down at the warmup/cooldown rate, over several epochs
+        let mut stake_history = StakeHistory::default();
+        let mut effective = 1_000;
+        for epoch in 4..8 {
+            stake_history.add(
+                epoch,
+                StakeHistoryEntry {
+                    effective,
+                    activating: 0,
+                    deactivating: effective,
+                },
+            );
+            effective -= effective / 4;
+        }
+        let stake_state = new_delegated_stake_state(1_000, 4, Lockup::default());
+        let clock_at = |epoch| Clock {
+            epoch,
+            ..Clock::default()
+        };
+
+        // Still fully staked in the deactivation epoch
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                401,
+                1_500,
+                100,
+                &clock_at(4),
+                &stake_history,
+                None
+            ),
+            Err(StakeError::InsufficientStake)
+        );
+        // A quarter cooled down after one epoch, the rest is still locked
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                650,
+                1_500,
+                100,
+                &clock_at(5),
+                &stake_history,
+                None
+            ),
+            Ok(850)
+        );
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                651,
+                1_500,
+                100,
+                &clock_at(5),
+                &stake_history,
+                None
+            ),
+            Err(StakeError::InsufficientStake)
+        );
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_500,
+                1_500,
+                100,
+                &clock_at(6),
+                &stake_history,
+                None
+            ),
+            Err(StakeError::InsufficientStake)
+        );
+        // and a little more is unlocked each epoch
+        let staked = 1_000 - 250 - 187;
+        assert_eq!(
+            withdraw(
+                &stake_state,
+                1_400 - staked,
+                1_500,
+                100,
+                &clock_at(6),
+                &stake_history,
+                None
+            ),
+            Ok(100 + staked)
+        );
+    }
+}
@@ -1,8 +1,49 @@
-use solana_account::{AccountSharedData, WritableAccount};
-use solana_clock::Clock;
+use solana_account::{AccountSharedData, ReadableAccount, WritableAccount};
+use solana_clock::{Clock, Epoch, Slot};
+use solana_epoch_schedule::EpochSchedule;
+use solana_instruction_error::InstructionError;
 use solana_pubkey::Pubkey;
 use solana_sdk_ids::vote::id;
+use solana_vote_interface::error::VoteError;
 use solana_vote_interface::state::{VoteInit, VoteStateV3, VoteStateVersions};
+use std::fmt;
+
+/// Reasons [`update_commission`] might fail. The vote interface's [`VoteError`]
+/// has no variant for an out of range commission, so it is wrapped here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommissionError {
+    /// The new commission is over 100 percent
+    CommissionTooHigh(u8),
+    Vote(VoteError),
+}
+
+impl std::error::Error for CommissionError {}
+
+impl fmt::Display for CommissionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CommissionTooHigh(commission) => {
+                write!(f, "commission {commission} is over 100 percent")
+            }
+            Self::Vote(err) => err.fmt(f),
+        }
+    }
+}
+
+impl From<VoteError> for CommissionError {
+    fn from(err: VoteError) -> Self {
+        Self::Vote(err)
+    }
+}
+
+impl From<CommissionError> for InstructionError {
+    fn from(err: CommissionError) -> Self {
+        match err {
+            CommissionError::CommissionTooHigh(_) => InstructionError::InvalidArgument,
+            CommissionError::Vote(err) => InstructionError::Custom(err as u32),
+        }
+    }
+}
 
 pub fn create_account_with_authorized(
     node_pubkey: &Pubkey,
@@ -31,3 +72,201 @@ pub fn create_account_with_authorized(
 
     vote_account
 }
+
+/// Reads the [`VoteStateV3`] back out of a vote account.
+pub fn get_vote_state(account: &AccountSharedData) -> Result<VoteStateV3, InstructionError> {
+    if account.owner() != &id() {
+        return Err(InstructionError::InvalidAccountOwner);
+    }
+    VoteStateV3::deserialize(account.data())
+}
+
+/// The identity of the validator voting with the vote account.
+pub fn node_pubkey(account: &AccountSharedData) -> Result<Pubkey, InstructionError> {
+    get_vote_state(account).map(|vote_state| vote_state.node_pubkey)
+}
+
+/// The commission percentage taken from the vote account's rewards.
+pub fn commission(account: &AccountSharedData) -> Result<u8, InstructionError> {
+    get_vote_state(account).map(|vote_state| vote_state.commission)
+}
+
+/// The authority allowed to withdraw from the vote account.
+pub fn authorized_withdrawer(account: &AccountSharedData) -> Result<Pubkey, InstructionError> {
+    get_vote_state(account).map(|vote_state| vote_state.authorized_withdrawer)
+}
+
+/// The authority allowed to vote in `epoch`, if any.
+pub fn authorized_voter(
+    account: &AccountSharedData,
+    epoch: Epoch,
+) -> Result<Option<Pubkey>, InstructionError> {
+    get_vote_state(account).map(|vote_state| vote_state.get_authorized_voter(epoch))
+}
+
+/// Sets the commission of `vote_state` to `new_commission` percent.
+///
+/// Decreasing the commission is always allowed, but to keep validators from
+/// raising it just before rewards are paid out, an increase fails with
+/// [`VoteError::CommissionUpdateTooLate`] in the second half of an epoch.
+/// Callers convert the error into an [`InstructionError`] with `into()`.
+pub fn update_commission(
+    vote_state: &mut VoteStateV3,
+    new_commission: u8,
+    clock: &Clock,
+    epoch_schedule: &EpochSchedule,
+) -> Result<(), CommissionError> {
+    if new_commission > 100 {
+        return Err(CommissionError::CommissionTooHigh(new_commission));
+    }
+    if new_commission > vote_state.commission
+        && !is_commission_update_allowed(clock.slot, epoch_schedule)
+    {
+        return Err(VoteError::CommissionUpdateTooLate.into());
+    }
+    vote_state.commission = new_commission;
+    Ok(())
+}
+
+/// Whether a commission increase is allowed at `slot`, i.e. whether `slot` is
+/// in the first half of its epoch. Always allowed during warmup epochs.
+pub fn is_commission_update_allowed(slot: Slot, epoch_schedule: &EpochSchedule) -> bool {
+    match slot
+        .saturating_sub(epoch_schedule.first_normal_slot)
+        .checked_rem(epoch_schedule.slots_per_epoch)
+    {
+        Some(relative_slot) => relative_slot.saturating_mul(2) <= epoch_schedule.slots_per_epoch,
+        // only reachable with an empty epoch
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock_at(slot: Slot) -> Clock {
+        Clock {
+            slot,
+            ..Clock::default()
+        }
+    }
+
+    #[test]
+    fn test_update_commission() {
+        let epoch_schedule = EpochSchedule::without_warmup();
+        let slots_per_epoch = epoch_schedule.slots_per_epoch;
+        let early = clock_at(slots_per_epoch + 1);
+        let late = clock_at(2 * slots_per_epoch - 1);
+        let mut vote_state = VoteStateV3 {
+            commission: 10,
+            ..VoteStateV3::default()
+        };
+
+        // decreases are allowed any time
+        update_commission(&mut vote_state, 5, &late, &epoch_schedule).unwrap();
+        assert_eq!(vote_state.commission, 5);
+
+        // increases only in the first half of the epoch
+        assert_eq!(
+            update_commission(&mut vote_state, 20, &late, &epoch_schedule),
+            Err(CommissionError::Vote(VoteError::CommissionUpdateTooLate))
+        );
+        assert_eq!(vote_state.commission, 5);
+        update_commission(&mut vote_state, 20, &early, &epoch_schedule).unwrap();
+        assert_eq!(vote_state.commission, 20);
+
+        assert_eq!(
+            update_commission(&mut vote_state, 101, &early, &epoch_schedule),
+            Err(CommissionError::CommissionTooHigh(101))
+        );
+        assert_eq!(vote_state.commission, 20);
+    }
+
+    #[test]
+    fn test_commission_error_into_instruction_error() {
+        assert_eq!(
+            InstructionError::from(CommissionError::CommissionTooHigh(101)),
+            InstructionError::InvalidArgument
+        );
+        assert_eq!(
+            InstructionError::from(CommissionError::Vote(VoteError::CommissionUpdateTooLate)),
+            InstructionError::Custom(VoteError::CommissionUpdateTooLate as u32)
+        );
+    }
+
+    #[test]
+    fn test_is_commission_update_allowed() {
+        let epoch_schedule = EpochSchedule::without_warmup();
+        let slots_per_epoch = epoch_schedule.slots_per_epoch;
+        assert!(is_commission_update_allowed(0, &epoch_schedule));
+        assert!(is_commission_update_allowed(
+            slots_per_epoch / 2,
+            &epoch_schedule
+        ));
+        assert!(!is_commission_update_allowed(
+            slots_per_epoch / 2 + 1,
+            &epoch_schedule
+        ));
+        assert!(is_commission_update_allowed(
+            slots_per_epoch,
+            &epoch_schedule
+        ));
+
+        // always allowed during warmup
+        let epoch_schedule = EpochSchedule::default();
+        assert!(is_commission_update_allowed(
+            epoch_schedule.first_normal_slot - 1,
+            &epoch_schedule
+        ));
+    }
+
+    #[test]
+    fn test_vote_state_accessors() {
+        let node_identity = Pubkey::new_unique();
+        let authorized_voter_pubkey = Pubkey::new_unique();
+        let authorized_withdrawer_pubkey = Pubkey::new_unique();
+        let account = create_account_with_authorized(
+            &node_identity,
+            &authorized_voter_pubkey,
+            &authorized_withdrawer_pubkey,
+            42,
+            100,
+        );
+
+        let vote_state = get_vote_state(&account).unwrap();
+        assert_eq!(vote_state.node_pubkey, node_identity);
+        assert_eq!(node_pubkey(&account), Ok(node_identity));
+        assert_eq!(commission(&account), Ok(42));
+        assert_eq!(
+            authorized_withdrawer(&account),
+            Ok(authorized_withdrawer_pubkey)
+        );
+        assert_eq!(
+            authorized_voter(&account, 0),
+            Ok(Some(authorized_voter_pubkey))
+        );
+    }
+
+    #[test]
+    fn test_get_vote_state_invalid_account() {
+        let mut account = create_account_with_authorized(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            0,
+            100,
+        );
+        account.set_owner(Pubkey::new_unique());
+        assert_eq!(
+            get_vote_state(&account),
+            Err(InstructionError::InvalidAccountOwner)
+        );
+
+        let account = AccountSharedData::new(100, 0, &id());
+        assert_eq!(
+            get_vote_state(&account),
+            Err(InstructionError::InvalidAccountData)
+        );
+    }
+}
@@ -28,3 +28,30 @@ pub fn activate_feature(genesis_config: &mut GenesisConfig, feature_id: Pubkey)
         )),
     );
 }
+
+/// The feature set in effect at genesis: the known features whose feature
+/// account in `genesis_config` has been activated.
+pub fn genesis_feature_set(genesis_config: &GenesisConfig) -> FeatureSet {
+    let mut feature_set = FeatureSet::default();
+    let feature_ids: Vec<Pubkey> = feature_set.inactive().iter().copied().collect();
+    for feature_id in feature_ids {
+        let activated_at = genesis_config
+            .accounts
+            .get(&feature_id)
+            .and_then(feature::from_account)
+            .and_then(|feature| feature.activated_at);
+        if let Some(slot) = activated_at {
+            feature_set.activate(&feature_id, slot);
+        }
+    }
+    feature_set
+}
+
+/// Total lamports held by the genesis accounts, i.e. the initial supply.
+pub fn total_lamports(genesis_config: &GenesisConfig) -> u64 {
+    genesis_config
+        .accounts
+        .values()
+        .map(|account| account.lamports)
+        .sum()
+}
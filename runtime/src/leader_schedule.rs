@@ -0,0 +1,128 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use solana_clock::Epoch;
+use solana_pubkey::Pubkey;
+use std::collections::HashMap;
+
+/// Number of consecutive slots a leader is scheduled for.
+pub const NUM_CONSECUTIVE_LEADER_SLOTS: u64 = 4;
+
+/// Computes the leader of every slot of `epoch` from the `(node pubkey, stake)`
+/// pairs, picking each leader with a probability proportional to its stake.
+///
+/// The schedule is deterministic: the same stakes, epoch and seed always give
+/// the same schedule, whatever the order of `stakes`. Leaders are drawn for
+/// runs of [`NUM_CONSECUTIVE_LEADER_SLOTS`] slots. Returns an empty schedule
+/// if no node has any stake.
+pub fn compute_leader_schedule(
+    stakes: &[(Pubkey, u64)],
+    epoch: Epoch,
+    slots_per_epoch: u64,
+    seed: [u8; 32],
+) -> Vec<Pubkey> {
+    let stakes = sort_stakes(stakes);
+    let (keys, stakes): (Vec<_>, Vec<_>) = stakes.into_iter().unzip();
+    let Ok(weighted_index) = WeightedIndex::new(stakes) else {
+        return vec![];
+    };
+
+    // Mix in the epoch so that every epoch gets a different schedule
+    let mut seed = seed;
+    for (byte, epoch_byte) in seed.iter_mut().zip(epoch.to_le_bytes()) {
+        *byte ^= epoch_byte;
+    }
+    let rng = &mut ChaChaRng::from_seed(seed);
+    let mut current_slot_leader = Pubkey::default();
+    (0..slots_per_epoch)
+        .map(|slot_index| {
+            if slot_index % NUM_CONSECUTIVE_LEADER_SLOTS == 0 {
+                current_slot_leader = keys[weighted_index.sample(rng)];
+            }
+            current_slot_leader
+        })
+        .collect()
+}
+
+// Merges the stakes of duplicate pubkeys, drops the unstaked ones, and sorts
+// by stake, then pubkey, both descending, so that the input order doesn't
+// matter.
+fn sort_stakes(stakes: &[(Pubkey, u64)]) -> Vec<(Pubkey, u64)> {
+    let mut merged = HashMap::<Pubkey, u64>::with_capacity(stakes.len());
+    for (pubkey, stake) in stakes {
+        let merged_stake = merged.entry(*pubkey).or_default();
+        *merged_stake = merged_stake.saturating_add(*stake);
+    }
+    let mut stakes: Vec<_> = merged.into_iter().filter(|(_, stake)| *stake > 0).collect();
+    stakes.sort_unstable_by(|(l_pubkey, l_stake), (r_pubkey, r_stake)| {
+        r_stake.cmp(l_stake).then_with(|| r_pubkey.cmp(l_pubkey))
+    });
+    stakes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_leader_schedule_deterministic() {
+        let stakes: Vec<_> = (1..=5)
+            .map(|stake| (Pubkey::new_unique(), stake * 100))
+            .collect();
+        let seed = [7; 32];
+
+        let schedule = compute_leader_schedule(&stakes, 3, 64, seed);
+        assert_eq!(schedule.len(), 64);
+        assert_eq!(schedule, compute_leader_schedule(&stakes, 3, 64, seed));
+        let mut reversed = stakes.clone();
+        reversed.reverse();
+        assert_eq!(schedule, compute_leader_schedule(&reversed, 3, 64, seed));
+
+        // leaders are scheduled for consecutive slots
+        for slots in schedule.chunks(NUM_CONSECUTIVE_LEADER_SLOTS as usize) {
+            assert!(slots.iter().all(|leader| *leader == slots[0]));
+        }
+
+        assert_ne!(schedule, compute_leader_schedule(&stakes, 4, 64, seed));
+        assert_ne!(schedule, compute_leader_schedule(&stakes, 3, 64, [8; 32]));
+    }
+
+    #[test]
+    fn test_compute_leader_schedule_stake_weighted() {
+        let small = Pubkey::new_unique();
+        let large = Pubkey::new_unique();
+        let unstaked = Pubkey::new_unique();
+        let stakes = [(small, 1), (large, 9), (unstaked, 0)];
+
+        let schedule = compute_leader_schedule(&stakes, 0, 4_000, [0; 32]);
+        let num_slots = |pubkey| schedule.iter().filter(|leader| **leader == pubkey).count();
+        assert!(num_slots(large) > 4 * num_slots(small));
+        assert!(num_slots(small) > 0);
+        assert_eq!(num_slots(unstaked), 0);
+    }
+
+    #[test]
+    fn test_compute_leader_schedule_duplicate_pubkeys() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let seed = [3; 32];
+
+        let merged = compute_leader_schedule(&[(a, 6), (b, 3)], 0, 64, seed);
+        assert_eq!(
+            compute_leader_schedule(&[(a, 5), (b, 3), (a, 1)], 0, 64, seed),
+            merged
+        );
+        assert_eq!(
+            compute_leader_schedule(&[(b, 3), (a, 1), (b, 0), (a, 5)], 0, 64, seed),
+            merged
+        );
+        assert_eq!(sort_stakes(&[(a, 5), (b, 3), (a, 1)]), vec![(a, 6), (b, 3)]);
+    }
+
+    #[test]
+    fn test_compute_leader_schedule_no_stake() {
+        assert!(compute_leader_schedule(&[], 0, 32, [0; 32]).is_empty());
+        let stakes = [(Pubkey::new_unique(), 0)];
+        assert!(compute_leader_schedule(&stakes, 0, 32, [0; 32]).is_empty());
+    }
+}
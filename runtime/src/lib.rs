@@ -1 +1,2 @@
 pub mod genesis_utils;
+pub mod leader_schedule;
@@ -1,6 +1,7 @@
 use crate::commands;
 use blockchain_net_utils::VALIDATOR_PORT_RANGE_STR;
 use clap::{crate_description, crate_name, crate_version, Command};
+use solana_clock::Slot;
 use solana_hash::Hash;
 use std::str::FromStr;
 
@@ -26,6 +27,11 @@ pub fn command(default_args: &DefaultArgs) -> Command {
         .version(crate_version!());
 
     commands::run::add_args(command, default_args)
+        .args_conflicts_with_subcommands(true)
+        .subcommand(commands::init::command(default_args))
+        .subcommand(commands::print_config::command(default_args))
+        .subcommand(commands::slot_meta::command(default_args))
+        .subcommand(commands::verify_genesis::command(default_args))
 }
 
 pub fn parse_port_validator(port: &str) -> Result<u16, String> {
@@ -36,3 +42,63 @@ pub fn parse_port_validator(port: &str) -> Result<u16, String> {
 pub(crate) fn parse_hash_validator(hash: &str) -> Result<Hash, String> {
     Hash::from_str(hash).map_err(|e| format!("{e:?}"))
 }
+
+/// Parses a `SLOT:HASH` pair, as taken by `--require-bank-hash-at`.
+pub(crate) fn parse_slot_hash(slot_hash: &str) -> Result<(Slot, Hash), String> {
+    let (slot, hash) = slot_hash
+        .split_once(':')
+        .ok_or_else(|| format!("expected SLOT:HASH, got {slot_hash}"))?;
+    let slot = slot
+        .parse::<Slot>()
+        .map_err(|err| format!("invalid slot {slot}: {err}"))?;
+    let hash = Hash::from_str(hash).map_err(|err| format!("invalid hash {hash}: {err}"))?;
+    Ok((slot, hash))
+}
+
+/// Parses `--rocksdb-keep-log-file-num`. Rocksdb needs to keep at least the
+/// info log file it is writing to, so 0 is rejected.
+pub(crate) fn parse_keep_log_file_num(num: &str) -> Result<usize, String> {
+    match num.parse::<usize>() {
+        Ok(0) => Err("must keep at least 1 log file".to_string()),
+        Ok(num) => Ok(num),
+        Err(err) => Err(format!("Unable to parse {num}: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_slot_hash() {
+        let hash = Hash::new_unique();
+        assert_eq!(parse_slot_hash(&format!("42:{hash}")), Ok((42, hash)));
+
+        assert_eq!(
+            parse_slot_hash("42"),
+            Err("expected SLOT:HASH, got 42".to_string())
+        );
+        assert!(parse_slot_hash(&format!("-1:{hash}"))
+            .unwrap_err()
+            .starts_with("invalid slot -1"));
+        assert!(parse_slot_hash("42:not-a-hash")
+            .unwrap_err()
+            .starts_with("invalid hash not-a-hash"));
+        assert!(parse_slot_hash(&format!("42:{hash}:{hash}"))
+            .unwrap_err()
+            .starts_with("invalid hash"));
+    }
+
+    #[test]
+    fn test_parse_keep_log_file_num() {
+        assert_eq!(parse_keep_log_file_num("1"), Ok(1));
+        assert_eq!(parse_keep_log_file_num("10"), Ok(10));
+        assert_eq!(
+            parse_keep_log_file_num("0"),
+            Err("must keep at least 1 log file".to_string())
+        );
+        assert!(parse_keep_log_file_num("-1")
+            .unwrap_err()
+            .starts_with("Unable to parse -1"));
+    }
+}
@@ -0,0 +1,128 @@
+use crate::cli::DefaultArgs;
+use crate::commands::{required_arg, FromClapArgMatches};
+use blockchain_accounts_db::hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE;
+use blockchain_ledger::blockstore::{create_new_ledger, Blockstore};
+use blockchain_ledger::blockstore_options::LedgerColumnOptions;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use solana_genesis_config::{GenesisConfig, DEFAULT_GENESIS_FILE};
+use std::error;
+use std::path::PathBuf;
+
+pub const COMMAND: &str = "init";
+
+pub fn command(default_args: &DefaultArgs) -> Command {
+    Command::new(COMMAND)
+        .about("Initialize an empty ledger")
+        .arg(
+            Arg::new("ledger_path")
+                .short('l')
+                .long("ledger")
+                .value_name("DIR")
+                .default_value(default_args.ledger_path)
+                .help("Use DIR as ledger location"),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .action(ArgAction::SetTrue)
+                .help("Initialize the ledger even if it already contains data"),
+        )
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InitArgs {
+    pub ledger_path: PathBuf,
+    pub force: bool,
+}
+
+impl FromClapArgMatches for InitArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> crate::commands::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(InitArgs {
+            ledger_path: required_arg::<String>(matches, "ledger_path", "--ledger is required")?
+                .into(),
+            force: matches.get_flag("force"),
+        })
+    }
+}
+
+/// Creates a new ledger, using the genesis config found in the ledger
+/// directory if there is one, or the default genesis config otherwise.
+pub fn execute(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+    let init_args = InitArgs::from_clap_arg_match(matches)?;
+    let ledger_path = init_args.ledger_path;
+
+    // Creating the ledger destroys the blockstore anyway, so an empty one can
+    // be removed right away.
+    if !init_args.force && !Blockstore::destroy_if_empty(&ledger_path)? {
+        return Err(format!(
+            "ledger at '{}' already contains data, use --force to overwrite it",
+            ledger_path.display()
+        )
+        .into());
+    }
+
+    let genesis_config = if ledger_path.join(DEFAULT_GENESIS_FILE).exists() {
+        GenesisConfig::load(&ledger_path)?
+    } else {
+        GenesisConfig::default()
+    };
+    create_new_ledger(
+        &ledger_path,
+        &genesis_config,
+        MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        LedgerColumnOptions::default(),
+    )?;
+
+    println!("Initialized ledger at '{}'", ledger_path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::subcommand_matches;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_init() {
+        let ledger_path = TempDir::new().unwrap();
+        let matches =
+            subcommand_matches(COMMAND, &["--ledger", ledger_path.path().to_str().unwrap()]);
+
+        execute(&matches).unwrap();
+        assert!(ledger_path.path().join(DEFAULT_GENESIS_FILE).exists());
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert_eq!(blockstore.max_block_height().unwrap(), None);
+        drop(blockstore);
+
+        // An empty ledger can be initialized again, reusing its genesis config
+        execute(&matches).unwrap();
+    }
+
+    #[test]
+    fn test_init_refuses_ledger_with_data() {
+        let ledger_path = TempDir::new().unwrap();
+        let ledger = ledger_path.path().to_str().unwrap();
+        execute(&subcommand_matches(COMMAND, &["--ledger", ledger])).unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            blockstore.set_block_height(1, 1).unwrap();
+        }
+
+        assert!(execute(&subcommand_matches(COMMAND, &["--ledger", ledger])).is_err());
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert_eq!(blockstore.get_block_height(1).unwrap(), Some(1));
+        drop(blockstore);
+
+        execute(&subcommand_matches(
+            COMMAND,
+            &["--ledger", ledger, "--force"],
+        ))
+        .unwrap();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        assert_eq!(blockstore.get_block_height(1).unwrap(), None);
+    }
+}
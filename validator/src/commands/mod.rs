@@ -1,4 +1,8 @@
+pub mod init;
+pub mod print_config;
 pub mod run;
+pub mod slot_meta;
+pub mod verify_genesis;
 
 use clap::parser::MatchesError;
 use std::fmt::Debug;
@@ -20,3 +24,71 @@ pub trait FromClapArgMatches {
     where
         Self: Sized;
 }
+
+/// Returns the value of the optional argument `id`.
+pub fn opt_arg<T>(matches: &clap::ArgMatches, id: &str) -> Result<Option<T>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    Ok(matches.try_get_one::<T>(id)?.cloned())
+}
+
+/// Returns the value of the argument `id`, failing with `err_msg` if it is
+/// absent.
+pub fn required_arg<T>(matches: &clap::ArgMatches, id: &str, err_msg: &str) -> Result<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    opt_arg(matches, id)?.ok_or_else(|| Error::Dynamic(err_msg.into()))
+}
+
+/// Parses `args` as the arguments of the validator subcommand `name` and
+/// returns the subcommand's matches.
+#[cfg(test)]
+pub(crate) fn subcommand_matches(name: &str, args: &[&str]) -> clap::ArgMatches {
+    let matches = crate::cli::command(&crate::cli::DefaultArgs::new())
+        .try_get_matches_from(["blockchain-validator", name].iter().chain(args))
+        .unwrap();
+    let (subcommand, matches) = matches.subcommand().unwrap();
+    assert_eq!(subcommand, name);
+    matches.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, Command};
+
+    fn command() -> Command {
+        Command::new("test")
+            .arg(
+                Arg::new("port")
+                    .long("port")
+                    .value_parser(clap::value_parser!(u16)),
+            )
+            .arg(Arg::new("name").long("name"))
+    }
+
+    #[test]
+    fn test_opt_arg() {
+        let matches = command().get_matches_from(["test", "--port", "8001"]);
+        assert_eq!(opt_arg::<u16>(&matches, "port").unwrap(), Some(8001));
+        assert_eq!(opt_arg::<String>(&matches, "name").unwrap(), None);
+        // Asking for the wrong type is an error rather than a panic
+        assert!(matches!(
+            opt_arg::<String>(&matches, "port"),
+            Err(Error::Clap(_))
+        ));
+    }
+
+    #[test]
+    fn test_required_arg() {
+        let matches = command().get_matches_from(["test", "--name", "validator"]);
+        assert_eq!(
+            required_arg::<String>(&matches, "name", "name is required").unwrap(),
+            "validator"
+        );
+        let err = required_arg::<u16>(&matches, "port", "port is required").unwrap_err();
+        assert_eq!(err.to_string(), "port is required");
+    }
+}
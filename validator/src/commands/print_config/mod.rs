@@ -0,0 +1,125 @@
+use crate::cli::DefaultArgs;
+use crate::commands::run::args::RunArgs;
+use crate::commands::run::execute::{bind_addresses, validator_config};
+use crate::commands::{run, FromClapArgMatches};
+use blockchain_ledger::blockstore_options::BlockstoreOptions;
+use clap::{ArgMatches, Command};
+use solana_signer::Signer;
+use std::error;
+use std::io::Write;
+
+pub const COMMAND: &str = "print-config";
+
+pub fn command(default_args: &DefaultArgs) -> Command {
+    run::add_args(
+        Command::new(COMMAND).about(
+            "Print the configuration resolved from the run arguments without starting the \
+             validator",
+        ),
+        default_args,
+    )
+}
+
+pub fn execute(matches: &ArgMatches, out: &mut impl Write) -> Result<(), Box<dyn error::Error>> {
+    let run_args = RunArgs::from_clap_arg_match(matches)?;
+    let bind_addresses = bind_addresses(matches)?;
+    let validator_config = validator_config(matches, &run_args, &bind_addresses)?;
+
+    let config = serde_json::json!({
+        "identity": run_args.identity_keypair.pubkey().to_string(),
+        "logfile": run_args.logfile,
+        "entrypoints": run_args.entrypoints,
+        "bind_addresses": &*bind_addresses,
+        "vote_account": run_args.vote_account.map(|pubkey| pubkey.to_string()),
+        "voting_disabled": validator_config.voting_disabled,
+        "rpc_addrs": validator_config.rpc_addrs,
+        "full_rpc_api": validator_config.full_rpc_api,
+        "require_tower": validator_config.require_tower,
+        "check_ledger": validator_config.check_ledger,
+        "expected_genesis_hash": validator_config
+            .expected_genesis_hash
+            .map(|hash| hash.to_string()),
+        "required_bank_hash": validator_config
+            .required_bank_hash
+            .map(|(slot, hash)| (slot, hash.to_string())),
+        "max_genesis_archive_unpacked_size": validator_config.max_genesis_archive_unpacked_size,
+        "blockstore_options": blockstore_options_json(&validator_config.blockstore_options),
+    });
+    serde_json::to_writer_pretty(&mut *out, &config)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn blockstore_options_json(options: &BlockstoreOptions) -> serde_json::Value {
+    serde_json::json!({
+        "access_type": format!("{:?}", options.access_type),
+        "recovery_mode": options.recovery_mode.as_ref().map(|mode| format!("{mode:?}")),
+        "enforce_ulimit_nofile": options.enforce_ulimit_nofile,
+        "column_options": format!("{:?}", options.column_options),
+        "num_rocksdb_compaction_threads": options.num_rocksdb_compaction_threads,
+        "num_rocksdb_flush_threads": options.num_rocksdb_flush_threads,
+        "max_open_files": options.max_open_files,
+        "max_total_wal_size_bytes": options.max_total_wal_size_bytes,
+        "keep_log_file_num": options.keep_log_file_num,
+        "enable_auto_compactions_for_maintenance": options.enable_auto_compactions_for_maintenance,
+        "slots_stats_capacity": options.slots_stats_capacity,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::subcommand_matches;
+    use solana_keypair::{write_keypair_file, Keypair};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_print_config() {
+        let tmp_dir = TempDir::new().unwrap();
+        let identity_path = tmp_dir.path().join("identity.json");
+        let identity = Keypair::new();
+        write_keypair_file(&identity, &identity_path).unwrap();
+
+        let matches = subcommand_matches(
+            COMMAND,
+            &[
+                "--identity",
+                identity_path.to_str().unwrap(),
+                "--entrypoint",
+                "127.0.0.1:8001",
+                "--bind-address",
+                "127.0.0.1",
+                "--rpc-port",
+                "8899",
+                "--private-rpc",
+                "--no-voting",
+                "--require-tower",
+                "--rocksdb-max-open-files",
+                "1000",
+                "--rocksdb-keep-log-file-num",
+                "3",
+            ],
+        );
+
+        let mut out = Vec::new();
+        execute(&matches, &mut out).unwrap();
+        let config: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(config["identity"], identity.pubkey().to_string());
+        assert_eq!(config["entrypoints"][0], "127.0.0.1:8001");
+        assert_eq!(config["bind_addresses"], serde_json::json!(["127.0.0.1"]));
+        assert_eq!(
+            config["rpc_addrs"],
+            serde_json::json!(["127.0.0.1:8899", "127.0.0.1:8900"])
+        );
+        assert_eq!(config["voting_disabled"], true);
+        assert_eq!(config["vote_account"], serde_json::Value::Null);
+        assert_eq!(config["require_tower"], true);
+        assert_eq!(config["check_ledger"], false);
+        assert_eq!(config["required_bank_hash"], serde_json::Value::Null);
+
+        let blockstore_options = &config["blockstore_options"];
+        assert_eq!(blockstore_options["access_type"], "Primary");
+        assert_eq!(blockstore_options["max_open_files"], 1000);
+        assert_eq!(blockstore_options["keep_log_file_num"], 3);
+    }
+}
@@ -1,13 +1,22 @@
-use crate::cli::{parse_hash_validator, parse_port_validator, DefaultArgs};
-use crate::commands::FromClapArgMatches;
+use crate::cli::{
+    parse_hash_validator, parse_keep_log_file_num, parse_port_validator, parse_slot_hash,
+    DefaultArgs,
+};
+use crate::commands::{opt_arg, required_arg, FromClapArgMatches};
 use blockchain_clap_utils::input_parsers::{parse_keypair_from_path, parse_pubkey_from_path};
-use blockchain_net_utils::{parse_host, parse_host_port, parse_port_range};
+use blockchain_ledger::blockstore_options::BlockstoreOptions;
+use blockchain_net_utils::{
+    parse_advertisable_host, parse_host, parse_host_port_cached, parse_port_range,
+};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
 use solana_signer::Signer;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+mod blockstore_options;
+
 pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
     command
         .arg(
@@ -18,6 +27,16 @@ pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
                 .value_parser(parse_keypair_from_path)
                 .help("Validator identity keypair"),
         )
+        .arg(
+            Arg::new("generate_identity")
+                .long("generate-identity")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("identity")
+                .help(
+                    "Run with a freshly generated identity keypair instead of --identity. \
+                     The keypair is not saved, so this is only meant for ephemeral dev nodes",
+                ),
+        )
         // The authorized_voter_keypairs argument provides a crucial security feature
         // by separating a validator's main identity from the key it uses for the high-frequency
         // task of signing votes.•Hot vs. Cold Keys: It allows you to use a "hot" key for
@@ -66,7 +85,7 @@ pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
                 .short('n')
                 .long("entrypoint")
                 .value_name("HOST:PORT")
-                .value_parser(parse_host_port)
+                .value_parser(parse_host_port_cached)
                 .action(ArgAction::Append)
                 .help("Rendezvous with the cluster at this gossip entrypoint"),
         )
@@ -83,6 +102,20 @@ pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
                 .value_parser(parse_port_validator)
                 .help("Enable JSON RPC on this port, and the next port for the RPC websocket"),
         )
+        .arg(
+            Arg::new("rpc_pubsub_port")
+                .long("rpc-pubsub-port")
+                .value_name("PORT")
+                .value_parser(parse_port_validator)
+                .requires("rpc_port")
+                .help("Port for the RPC websocket [default: the port after --rpc-port]"),
+        )
+        .arg(
+            Arg::new("full_rpc_api")
+                .long("full-rpc-api")
+                .action(ArgAction::SetTrue)
+                .help("Expose RPC methods for querying chain state and transaction history"),
+        )
         .arg(
             Arg::new("private_rpc")
                 .long("private-rpc")
@@ -110,6 +143,16 @@ pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
                 .value_parser(parse_hash_validator)
                 .help("Require the genesis have this hash"),
         )
+        .arg(
+            Arg::new("require_bank_hash_at")
+                .long("require-bank-hash-at")
+                .value_name("SLOT:HASH")
+                .value_parser(parse_slot_hash)
+                .help(
+                    "Refuse to start unless the ledger holds this bank hash for the slot, \
+                     guarding against booting on a corrupted or forked ledger",
+                ),
+        )
         .arg(
             Arg::new("bind_address")
                 .long("bind-address")
@@ -128,10 +171,18 @@ pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
                 .value_name("HOST")
                 .value_parser(parse_host)
                 .help(
-                    "IP address to bind the RPC port [default: 127.0.0.1 if --private-rpc is \
-                 present, otherwise use --bind-address]",
+                    "IP address to bind the RPC port [default: the loopback address (127.0.0.1 \
+                     or ::1, matching --bind-address) if --private-rpc is present, otherwise \
+                     use --bind-address]",
                 ),
         )
+        .arg(
+            Arg::new("gossip_host")
+                .long("gossip-host")
+                .value_name("HOST")
+                .value_parser(parse_advertisable_host)
+                .help("Gossip DNS name or IP address for the validator to advertise in gossip"),
+        )
         .arg(
             Arg::new("gossip_port")
                 .long("gossip-port")
@@ -139,6 +190,56 @@ pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
                 .value_parser(parse_port_validator)
                 .help("Gossip port number for the validator"),
         )
+        .arg(
+            Arg::new("check_ledger")
+                .long("check-ledger")
+                .action(ArgAction::SetTrue)
+                .help("Check the ledger for inconsistent slot metadata on startup"),
+        )
+        .arg(
+            Arg::new("rocksdb_max_open_files")
+                .long("rocksdb-max-open-files")
+                .value_name("NUMBER")
+                .value_parser(clap::value_parser!(i32).range(1..))
+                .help(
+                    "Maximum number of files the blockstore keeps open \
+                     [default: unlimited]",
+                ),
+        )
+        .arg(
+            Arg::new("rocksdb_max_total_wal_size")
+                .long("rocksdb-max-total-wal-size")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("Maximum total size of the blockstore write-ahead logs [default: 4 GiB]"),
+        )
+        .arg(
+            Arg::new("rocksdb_keep_log_file_num")
+                .long("rocksdb-keep-log-file-num")
+                .value_name("NUMBER")
+                .value_parser(parse_keep_log_file_num)
+                .help("Number of blockstore info log files to keep [default: 10]"),
+        )
+        .arg(
+            Arg::new("rocksdb_periodic_compaction_seconds")
+                .long("rocksdb-periodic-compaction-seconds")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help(
+                    "Age after which blockstore files of the columns cleaned up by \
+                     compaction are compacted [default: 86400]",
+                ),
+        )
+        .arg(
+            Arg::new("log_filter")
+                .long("log-filter")
+                .value_name("SPEC")
+                .help(
+                    "Log filter in RUST_LOG syntax, e.g. \"solana=info,blockchain=debug\". \
+                     Takes precedence over the RUST_LOG environment variable, which in turn \
+                     takes precedence over the default filter",
+                ),
+        )
         .arg(
             Arg::new("logfile")
                 .short('o')
@@ -149,16 +250,35 @@ pub fn add_args(command: Command, default_args: &DefaultArgs) -> Command {
                  SIGUSR1 signal to the validator process will cause it to re-open the log file",
                 ),
         )
+        .arg(
+            Arg::new("log_max_size_mb")
+                .long("log-max-size-mb")
+                .value_name("MEGABYTES")
+                .value_parser(clap::value_parser!(u64).range(1..=u64::MAX / (1024 * 1024)))
+                .help(
+                    "Rotate the log file once it grows past this size. The current file is \
+                     renamed with a .1 suffix, replacing any previous rotation",
+                ),
+        )
 }
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct RunArgs {
     pub identity_keypair: Arc<Keypair>,
     pub logfile: String,
+    pub log_max_size_mb: Option<u64>,
+    /// The `--entrypoint` values as given, sorted and deduplicated
+    pub entrypoint_strings: Vec<String>,
+    /// The resolved `--entrypoint` addresses, sorted and deduplicated
     pub entrypoints: Vec<SocketAddr>,
+    pub vote_account: Option<Pubkey>,
+    pub voting_disabled: bool,
+    pub full_rpc_api: bool,
+    /// Warnings about the arguments, to be logged once logging is set up
+    pub warnings: Vec<String>,
     // pub known_validators: Option<HashSet<Pubkey>>,
     // pub socket_addr_space: SocketAddrSpace,
     // pub rpc_bootstrap_config: RpcBootstrapConfig,
-    // pub blockstore_options: BlockstoreOptions,
+    pub blockstore_options: BlockstoreOptions,
 }
 
 impl FromClapArgMatches for RunArgs {
@@ -166,19 +286,36 @@ impl FromClapArgMatches for RunArgs {
     where
         Self: Sized,
     {
-        let identity_keypair = matches
-            .try_get_one::<Arc<Keypair>>("identity")?
-            .ok_or_else(|| {
-                crate::commands::Error::Dynamic(
-                    "Validator identity keypair is required (--identity)".into(),
-                )
-            })?;
-
-        let logfile = matches
-            .get_one::<String>("logfile")
-            .map(|s| s.to_string())
+        let mut warnings = vec![];
+        let identity_keypair: Arc<Keypair> = if matches.get_flag("generate_identity") {
+            let identity_keypair = Arc::new(Keypair::new());
+            warnings.push(format!(
+                "Using generated identity {}, it is lost when the validator exits",
+                identity_keypair.pubkey()
+            ));
+            identity_keypair
+        } else {
+            required_arg(
+                matches,
+                "identity",
+                "Validator identity keypair is required (--identity or --generate-identity)",
+            )?
+        };
+
+        let logfile = opt_arg::<String>(matches, "logfile")?
             .unwrap_or_else(|| format!("agave-validator-{}.log", identity_keypair.pubkey()));
 
+        let log_max_size_mb = opt_arg::<u64>(matches, "log_max_size_mb")?;
+
+        let mut entrypoint_strings: Vec<String> = matches
+            .get_raw("entrypoint")
+            .into_iter()
+            .flatten()
+            .map(|entrypoint| entrypoint.to_string_lossy().into_owned())
+            .collect();
+        entrypoint_strings.sort();
+        entrypoint_strings.dedup();
+
         let mut entrypoints: Vec<SocketAddr> = matches
             .get_many::<SocketAddr>("entrypoint")
             .into_iter()
@@ -189,6 +326,11 @@ impl FromClapArgMatches for RunArgs {
         entrypoints.sort();
         entrypoints.dedup();
 
+        let vote_account = opt_arg::<Pubkey>(matches, "vote_account")?;
+        let (voting_disabled, voting_warning) =
+            resolve_voting_disabled(matches.get_flag("no_voting"), vote_account.as_ref());
+        warnings.extend(voting_warning.map(String::from));
+
         // let known_validators = validators_set(
         //     &identity_keypair.pubkey(),
         //     matches,
@@ -199,13 +341,227 @@ impl FromClapArgMatches for RunArgs {
         // let socket_addr_space = SocketAddrSpace::new(matches.is_present("allow_private_addr"));
 
         Ok(RunArgs {
-            identity_keypair: identity_keypair.clone(),
+            identity_keypair,
             logfile,
+            log_max_size_mb,
+            entrypoint_strings,
             entrypoints,
+            vote_account,
+            voting_disabled,
+            full_rpc_api: matches.get_flag("full_rpc_api"),
+            warnings,
             // known_validators,
             // socket_addr_space,
             // rpc_bootstrap_config: RpcBootstrapConfig::from_clap_arg_match(matches)?,
-            // blockstore_options: BlockstoreOptions::from_clap_arg_match(matches)?,
+            blockstore_options: BlockstoreOptions::from_clap_arg_match(matches)?,
         })
     }
 }
+
+/// Entrypoints are compared by their `--entrypoint` values, as the resolved
+/// addresses depend on DNS at the time the arguments were parsed.
+impl PartialEq for RunArgs {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity_keypair == other.identity_keypair
+            && self.logfile == other.logfile
+            && self.log_max_size_mb == other.log_max_size_mb
+            && self.entrypoint_strings == other.entrypoint_strings
+            && self.vote_account == other.vote_account
+            && self.voting_disabled == other.voting_disabled
+            && self.full_rpc_api == other.full_rpc_api
+            && self.warnings == other.warnings
+            && self.blockstore_options == other.blockstore_options
+    }
+}
+
+/// Resolves whether voting is disabled from `--no-voting` and `--vote-account`,
+/// along with a warning when the combination is likely an operator mistake.
+fn resolve_voting_disabled(
+    no_voting: bool,
+    vote_account: Option<&Pubkey>,
+) -> (bool, Option<&'static str>) {
+    match (no_voting, vote_account) {
+        (true, Some(_)) => (
+            true,
+            Some("--vote-account is ignored because --no-voting is set, validator will not vote"),
+        ),
+        (true, None) => (true, None),
+        (false, Some(_)) => (false, None),
+        (false, None) => (
+            true,
+            Some("--vote-account not specified, validator will not vote"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli;
+    use solana_keypair::write_keypair_file;
+    use tempfile::TempDir;
+
+    fn run_args(tmp_dir: &TempDir, args: &[&str]) -> RunArgs {
+        let identity_path = tmp_dir.path().join("identity.json");
+        write_keypair_file(&Keypair::new(), &identity_path).unwrap();
+        let matches = cli::command(&DefaultArgs::new())
+            .try_get_matches_from(
+                [
+                    "blockchain-validator",
+                    "--identity",
+                    identity_path.to_str().unwrap(),
+                ]
+                .iter()
+                .chain(args),
+            )
+            .unwrap();
+        RunArgs::from_clap_arg_match(&matches).unwrap()
+    }
+
+    fn try_run_args(args: &[&str]) -> crate::commands::Result<RunArgs> {
+        let matches = cli::command(&DefaultArgs::new())
+            .try_get_matches_from(["blockchain-validator"].iter().chain(args))
+            .unwrap();
+        RunArgs::from_clap_arg_match(&matches)
+    }
+
+    #[test]
+    fn test_run_args_identity() {
+        let tmp_dir = TempDir::new().unwrap();
+        let identity_keypair = Keypair::new();
+        let identity_path = tmp_dir.path().join("identity.json");
+        write_keypair_file(&identity_keypair, &identity_path).unwrap();
+
+        let args = try_run_args(&["--identity", identity_path.to_str().unwrap()]).unwrap();
+        assert_eq!(args.identity_keypair.pubkey(), identity_keypair.pubkey());
+
+        let args = try_run_args(&["--generate-identity", "--no-voting"]).unwrap();
+        assert_ne!(args.identity_keypair.pubkey(), identity_keypair.pubkey());
+        assert_eq!(args.warnings.len(), 1);
+        assert!(args.warnings[0].contains(&args.identity_keypair.pubkey().to_string()));
+
+        let err = try_run_args(&[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validator identity keypair is required (--identity or --generate-identity)"
+        );
+
+        // Both at once is rejected by clap
+        assert!(cli::command(&DefaultArgs::new())
+            .try_get_matches_from([
+                "blockchain-validator",
+                "--identity",
+                identity_path.to_str().unwrap(),
+                "--generate-identity",
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_resolve_voting_disabled() {
+        let vote_account = Pubkey::new_unique();
+        assert_eq!(
+            resolve_voting_disabled(false, Some(&vote_account)),
+            (false, None)
+        );
+        assert_eq!(resolve_voting_disabled(true, None), (true, None));
+        assert!(matches!(
+            resolve_voting_disabled(false, None),
+            (true, Some(_))
+        ));
+        assert!(matches!(
+            resolve_voting_disabled(true, Some(&vote_account)),
+            (true, Some(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_args_voting() {
+        let tmp_dir = TempDir::new().unwrap();
+        let vote_keypair = Keypair::new();
+        let vote_account_path = tmp_dir.path().join("vote-account.json");
+        write_keypair_file(&vote_keypair, &vote_account_path).unwrap();
+        let vote_account_path = vote_account_path.to_str().unwrap();
+
+        let args = run_args(&tmp_dir, &["--vote-account", vote_account_path]);
+        assert_eq!(args.vote_account, Some(vote_keypair.pubkey()));
+        assert!(!args.voting_disabled);
+        assert!(args.warnings.is_empty());
+
+        let args = run_args(&tmp_dir, &["--no-voting"]);
+        assert!(args.voting_disabled);
+        assert!(args.warnings.is_empty());
+
+        // Conflicting arguments disable voting and are reported
+        let args = run_args(
+            &tmp_dir,
+            &["--no-voting", "--vote-account", vote_account_path],
+        );
+        assert!(args.voting_disabled);
+        assert_eq!(args.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_run_args_entrypoints() {
+        let tmp_dir = TempDir::new().unwrap();
+        let identity_path = tmp_dir.path().join("identity.json");
+        write_keypair_file(&Keypair::new(), &identity_path).unwrap();
+        let args = [
+            "--identity",
+            identity_path.to_str().unwrap(),
+            "--entrypoint",
+            "127.0.0.1:8001",
+            "--entrypoint",
+            "localhost:8002",
+            "--entrypoint",
+            "127.0.0.1:8001",
+        ];
+
+        let run_args = try_run_args(&args).unwrap();
+        assert_eq!(
+            run_args.entrypoint_strings,
+            vec!["127.0.0.1:8001".to_string(), "localhost:8002".to_string()]
+        );
+        assert_eq!(run_args.entrypoints.len(), 2);
+        assert_eq!(run_args, try_run_args(&args).unwrap());
+
+        // Resolved addresses don't take part in the comparison
+        let mut other = try_run_args(&args).unwrap();
+        other.entrypoints.reverse();
+        assert_eq!(run_args, other);
+        other.entrypoint_strings.pop();
+        assert_ne!(run_args, other);
+    }
+
+    #[test]
+    fn test_run_args_log_max_size_mb() {
+        let tmp_dir = TempDir::new().unwrap();
+        assert_eq!(run_args(&tmp_dir, &[]).log_max_size_mb, None);
+        assert_eq!(
+            run_args(&tmp_dir, &["--log-max-size-mb", "100"]).log_max_size_mb,
+            Some(100)
+        );
+
+        // The size in bytes must fit in a u64
+        let max_size_mb = u64::MAX / (1024 * 1024);
+        assert_eq!(
+            run_args(&tmp_dir, &["--log-max-size-mb", &max_size_mb.to_string()]).log_max_size_mb,
+            Some(max_size_mb)
+        );
+        assert!(cli::command(&DefaultArgs::new())
+            .try_get_matches_from([
+                "blockchain-validator",
+                "--generate-identity",
+                "--log-max-size-mb",
+                &(max_size_mb + 1).to_string(),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_run_args_full_rpc_api() {
+        let tmp_dir = TempDir::new().unwrap();
+        assert!(!run_args(&tmp_dir, &[]).full_rpc_api);
+        assert!(run_args(&tmp_dir, &["--full-rpc-api"]).full_rpc_api);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::commands::{opt_arg, FromClapArgMatches};
+use blockchain_ledger::blockstore_options::{
+    BlockstoreOptions, LedgerColumnOptions, DEFAULT_KEEP_LOG_FILE_NUM,
+    DEFAULT_MAX_TOTAL_WAL_SIZE_BYTES, DEFAULT_PERIODIC_COMPACTION_SECONDS,
+};
+use clap::ArgMatches;
+
+impl FromClapArgMatches for BlockstoreOptions {
+    fn from_clap_arg_match(matches: &ArgMatches) -> crate::commands::Result<Self> {
+        Ok(BlockstoreOptions {
+            max_open_files: opt_arg::<i32>(matches, "rocksdb_max_open_files")?,
+            max_total_wal_size_bytes: opt_arg::<u64>(matches, "rocksdb_max_total_wal_size")?
+                .unwrap_or(DEFAULT_MAX_TOTAL_WAL_SIZE_BYTES),
+            keep_log_file_num: opt_arg::<usize>(matches, "rocksdb_keep_log_file_num")?
+                .unwrap_or(DEFAULT_KEEP_LOG_FILE_NUM),
+            column_options: LedgerColumnOptions {
+                periodic_compaction_seconds: opt_arg::<u64>(
+                    matches,
+                    "rocksdb_periodic_compaction_seconds",
+                )?
+                .unwrap_or(DEFAULT_PERIODIC_COMPACTION_SECONDS),
+                ..LedgerColumnOptions::default()
+            },
+            ..BlockstoreOptions::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{self, DefaultArgs};
+
+    fn blockstore_options(args: &[&str]) -> BlockstoreOptions {
+        let matches = cli::command(&DefaultArgs::new())
+            .try_get_matches_from(["blockchain-validator"].iter().chain(args))
+            .unwrap();
+        BlockstoreOptions::from_clap_arg_match(&matches).unwrap()
+    }
+
+    #[test]
+    fn test_max_open_files() {
+        assert_eq!(blockstore_options(&[]).max_open_files, None);
+        assert_eq!(
+            blockstore_options(&["--rocksdb-max-open-files", "1024"]).max_open_files,
+            Some(1024)
+        );
+    }
+
+    #[test]
+    fn test_wal_size_and_log_retention() {
+        let options = blockstore_options(&[]);
+        assert_eq!(
+            options.max_total_wal_size_bytes,
+            DEFAULT_MAX_TOTAL_WAL_SIZE_BYTES
+        );
+        assert_eq!(options.keep_log_file_num, DEFAULT_KEEP_LOG_FILE_NUM);
+
+        let options = blockstore_options(&[
+            "--rocksdb-max-total-wal-size",
+            "1048576",
+            "--rocksdb-keep-log-file-num",
+            "2",
+        ]);
+        assert_eq!(options.max_total_wal_size_bytes, 1024 * 1024);
+        assert_eq!(options.keep_log_file_num, 2);
+
+        // rocksdb has to keep the log file it is writing to
+        assert!(cli::command(&DefaultArgs::new())
+            .try_get_matches_from(["blockchain-validator", "--rocksdb-keep-log-file-num", "0"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_periodic_compaction_seconds() {
+        assert_eq!(
+            blockstore_options(&[])
+                .column_options
+                .periodic_compaction_seconds,
+            DEFAULT_PERIODIC_COMPACTION_SECONDS
+        );
+        assert_eq!(
+            blockstore_options(&["--rocksdb-periodic-compaction-seconds", "3600"])
+                .column_options
+                .periodic_compaction_seconds,
+            3600
+        );
+    }
+}
@@ -1,40 +1,64 @@
 use crate::commands::{run::args::RunArgs, FromClapArgMatches};
+use crate::log_rotation;
 use blockchain_accounts_db::hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE;
 use blockchain_accounts_db::utils::create_and_canonicalize_directory;
 use blockchain_core::validator::{Validator, ValidatorConfig};
 use blockchain_gossip::cluster_info::{BindIpAddrs, NodeConfig};
 use blockchain_gossip::node::Node;
-use blockchain_net_utils::{find_available_port_in_range, parse_host, PortRange};
+use blockchain_net_utils::sockets::{bind_common_with_config, bind_to, SocketConfiguration};
+use blockchain_net_utils::PortRange;
 use clap::ArgMatches;
-use log::{error, info, warn};
+use log::{info, warn};
+use solana_clock::Slot;
 use solana_hash::Hash;
 use solana_keypair::Keypair;
-use solana_logger::{redirect_stderr_to_file, setup_with_default};
-use solana_pubkey::Pubkey;
+use solana_logger::{redirect_stderr_to_file, setup_with};
 use solana_signer::Signer;
+use std::env;
 use std::error;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 
 pub const DEFAULT_FILTER: &str = "solana=info,agave=info,blockchain=debug";
 
+/// File under the ledger path that records the gossip port of the previous
+/// run, so that the advertised port stays stable across restarts.
+const GOSSIP_PORT_FILE: &str = "gossip-port";
+
 pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<(), Box<dyn error::Error>> {
     let run_args = RunArgs::from_clap_arg_match(matches)?;
 
-    let identity_keypair = run_args.identity_keypair;
+    let identity_keypair = run_args.identity_keypair.clone();
 
-    let logfile = run_args.logfile;
-    let logfile = if logfile == "-" {
-        None
-    } else {
+    let logfile = prepare_logfile(&run_args.logfile)?;
+    if let Some(logfile) = &logfile {
         println!("log file: {logfile}");
-        Some(logfile)
-    };
-    let _logger_thread = redirect_stderr_to_file(logfile);
-    setup_with_default(DEFAULT_FILTER);
+    }
+    let _logger_thread = redirect_stderr_to_file(logfile.clone());
+    let rust_log = env::var("RUST_LOG").ok();
+    setup_with(&resolve_log_filter(
+        matches.get_one::<String>("log_filter").map(String::as_str),
+        rust_log.as_deref(),
+    ));
 
     info!("Starting validator with: {:#?}", std::env::args_os());
+    for warning in &run_args.warnings {
+        warn!("{warning}");
+    }
+    let _log_rotation_thread = match (logfile, run_args.log_max_size_mb) {
+        (Some(logfile), Some(max_size_mb)) => {
+            Some(log_rotation::spawn(logfile, max_size_mb * 1024 * 1024)?)
+        }
+        (None, Some(_)) => {
+            warn!("--log-max-size-mb is ignored when logging to standard error");
+            None
+        }
+        (_, None) => None,
+    };
 
     let authorized_voter_keypairs = match matches.try_get_one::<Vec<Arc<Keypair>>>("matches") {
         Ok(Some(keypairs)) => keypairs.clone(),
@@ -45,8 +69,6 @@ pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<(), Box<dyn e
     // allow it to be safely shared and potentially modified by multiple threads.
     let _authorized_voter_keypairs = Arc::new(RwLock::new(authorized_voter_keypairs));
 
-    let private_rpc = matches.get_flag("private_rpc");
-
     // Canonicalize ledger path to avoid issues with symlink creation
     let ledger_path = create_and_canonicalize_directory(ledger_path).map_err(|err| {
         format!(
@@ -55,59 +77,14 @@ pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<(), Box<dyn e
         )
     })?;
 
-    let _entrypoint_addrs = run_args.entrypoints;
+    let _entrypoint_addrs = &run_args.entrypoints;
 
-    let bind_addresses = {
-        let parsed = matches
-            .get_many::<IpAddr>("bind_address")
-            .expect("bind_address should always be present due to default")
-            .cloned()
-            .collect();
-        BindIpAddrs::new(parsed).map_err(|err| format!("invalid bind_addresses: {err}"))?
-    };
+    let bind_addresses = bind_addresses(matches)?;
+    let validator_config = validator_config(matches, &run_args, &bind_addresses)?;
 
-    let rpc_bind_address = if matches.contains_id("rpc_bind_address") {
-        *matches
-            .get_one::<IpAddr>("rpc_bind_address")
-            .expect("invalid rpc_bind_address")
-    } else if private_rpc {
-        parse_host("127.0.0.1")?
-    } else {
-        bind_addresses.primary()
-    };
-
-    let mut validator_config = ValidatorConfig {
-        require_tower: matches.get_flag("require_tower"),
-        max_genesis_archive_unpacked_size: MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
-        expected_genesis_hash: matches
-            .try_get_one::<Hash>("expected_genesis_hash")?
-            .copied(),
-        voting_disabled: matches.get_flag("no_voting"),
-        rpc_addrs: matches.try_get_one::<u16>("rpc_port")?.map(|rpc_port| {
-            (
-                SocketAddr::new(rpc_bind_address, *rpc_port),
-                SocketAddr::new(rpc_bind_address, *rpc_port + 1),
-            )
-        }),
-    };
-
-    let vote_account = match matches.try_get_one::<Arc<Pubkey>>("vote_account") {
-        Ok(Some(pubkey)) => pubkey.clone(),
-        Err(e) => {
-            if !validator_config.voting_disabled {
-                error!("--vote-account could not be parsed {e}, validator will not vote");
-                validator_config.voting_disabled = true;
-            }
-            Arc::new(Keypair::new().pubkey())
-        }
-        Ok(None) => {
-            if !validator_config.voting_disabled {
-                warn!("--vote-account not specified, validator will not vote");
-                validator_config.voting_disabled = true;
-            }
-            Arc::new(Keypair::new().pubkey())
-        }
-    };
+    let vote_account = run_args
+        .vote_account
+        .unwrap_or_else(|| Keypair::new().pubkey());
 
     let dynamic_port_range = matches
         .try_get_one::<PortRange>("dynamic_port_range")
@@ -115,10 +92,13 @@ pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<(), Box<dyn e
         .copied()
         .unwrap();
 
-    let advertised_ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let advertised_ip = matches
+        .get_one::<IpAddr>("gossip_host")
+        .copied()
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
 
     let gossip_port = match matches.try_get_one::<u16>("gossip_port")? {
-        None => find_available_port_in_range(bind_addresses.primary(), (0, 1))?,
+        None => select_gossip_port(&ledger_path, bind_addresses.primary())?,
         Some(&port) => port,
     };
 
@@ -127,6 +107,7 @@ pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<(), Box<dyn e
         gossip_port,
         port_range: dynamic_port_range,
         bind_ip_addrs: bind_addresses,
+        num_gossip_sockets: 1,
     };
 
     let node = Node::new_with_external_ip(&identity_keypair.pubkey(), node_config);
@@ -148,3 +129,296 @@ pub fn execute(matches: &ArgMatches, ledger_path: &Path) -> Result<(), Box<dyn e
 
     Ok(())
 }
+
+/// Resolves the addresses to bind to from `--bind-address`, the first one being
+/// the primary address.
+pub(crate) fn bind_addresses(matches: &ArgMatches) -> Result<BindIpAddrs, String> {
+    let parsed = matches
+        .get_many::<IpAddr>("bind_address")
+        .expect("bind_address should always be present due to default")
+        .cloned()
+        .collect();
+    BindIpAddrs::new(parsed).map_err(|err| format!("invalid bind_addresses: {err}"))
+}
+
+/// Assembles the configuration the validator is started with from the run
+/// arguments.
+pub(crate) fn validator_config(
+    matches: &ArgMatches,
+    run_args: &RunArgs,
+    bind_addresses: &BindIpAddrs,
+) -> Result<ValidatorConfig, Box<dyn error::Error>> {
+    let rpc_bind_address = resolve_rpc_bind_address(
+        matches.try_get_one::<IpAddr>("rpc_bind_address")?.copied(),
+        matches.get_flag("private_rpc"),
+        bind_addresses.primary(),
+    );
+
+    Ok(ValidatorConfig {
+        require_tower: matches.get_flag("require_tower"),
+        max_genesis_archive_unpacked_size: MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        expected_genesis_hash: matches
+            .try_get_one::<Hash>("expected_genesis_hash")?
+            .copied(),
+        voting_disabled: run_args.voting_disabled,
+        check_ledger: matches.get_flag("check_ledger"),
+        blockstore_options: run_args.blockstore_options.clone(),
+        rpc_addrs: rpc_addrs(
+            rpc_bind_address,
+            matches.try_get_one::<u16>("rpc_port")?.copied(),
+            matches.try_get_one::<u16>("rpc_pubsub_port")?.copied(),
+        )?,
+        full_rpc_api: run_args.full_rpc_api,
+        required_bank_hash: matches
+            .try_get_one::<(Slot, Hash)>("require_bank_hash_at")?
+            .copied(),
+    })
+}
+
+/// Resolves the log filter, by precedence: `--log-filter`, then `RUST_LOG`, then
+/// [`DEFAULT_FILTER`].
+fn resolve_log_filter(log_filter: Option<&str>, rust_log: Option<&str>) -> String {
+    log_filter
+        .or(rust_log)
+        .unwrap_or(DEFAULT_FILTER)
+        .to_string()
+}
+
+/// Prepares the log file for [`redirect_stderr_to_file`]: its parent directory
+/// is created if missing and canonicalized, and the file is opened for
+/// appending to fail early if it is not writable. Returns `None` for `-`, which
+/// logs to standard error.
+fn prepare_logfile(logfile: &str) -> io::Result<Option<String>> {
+    if logfile == "-" {
+        return Ok(None);
+    }
+    let path = Path::new(logfile);
+    let Some(file_name) = path.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("log file {logfile} does not name a file"),
+        ));
+    };
+    let log_dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let log_dir = create_and_canonicalize_directory(log_dir).map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!(
+                "unable to create log directory {}: {err}",
+                log_dir.display()
+            ),
+        )
+    })?;
+
+    let path = log_dir.join(file_name);
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("log file {} is not writable: {err}", path.display()),
+            )
+        })?;
+    path.into_os_string()
+        .into_string()
+        .map(Some)
+        .map_err(|path| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("log file {} is not valid UTF-8", Path::new(&path).display()),
+            )
+        })
+}
+
+/// Resolves the address to bind the RPC ports to: `--rpc-bind-address` if
+/// given, otherwise the loopback address of the same family as the primary bind
+/// address if `--private-rpc` is set, otherwise the primary bind address.
+fn resolve_rpc_bind_address(
+    rpc_bind_address: Option<IpAddr>,
+    private_rpc: bool,
+    primary_bind_address: IpAddr,
+) -> IpAddr {
+    match (rpc_bind_address, private_rpc, primary_bind_address) {
+        (Some(rpc_bind_address), _, _) => rpc_bind_address,
+        (None, true, IpAddr::V4(_)) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        (None, true, IpAddr::V6(_)) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        (None, false, primary_bind_address) => primary_bind_address,
+    }
+}
+
+/// Resolves the JSON RPC and RPC websocket addresses. The websocket listens on
+/// `rpc_pubsub_port` if given, otherwise on the port after `rpc_port`.
+fn rpc_addrs(
+    rpc_bind_address: IpAddr,
+    rpc_port: Option<u16>,
+    rpc_pubsub_port: Option<u16>,
+) -> Result<Option<(SocketAddr, SocketAddr)>, String> {
+    let Some(rpc_port) = rpc_port else {
+        return Ok(None);
+    };
+    let rpc_pubsub_port = match rpc_pubsub_port {
+        Some(rpc_pubsub_port) if rpc_pubsub_port == rpc_port => {
+            return Err(format!(
+                "--rpc-pubsub-port must differ from --rpc-port {rpc_port}"
+            ));
+        }
+        Some(rpc_pubsub_port) => rpc_pubsub_port,
+        None => rpc_port.checked_add(1).ok_or_else(|| {
+            format!("--rpc-port {rpc_port} leaves no room for the RPC websocket port")
+        })?,
+    };
+    Ok(Some((
+        SocketAddr::new(rpc_bind_address, rpc_port),
+        SocketAddr::new(rpc_bind_address, rpc_pubsub_port),
+    )))
+}
+
+/// Selects the gossip port when `--gossip-port` is absent: the port used by the
+/// previous run if it is still available, otherwise a fresh one from the OS.
+/// The selected port is persisted for the next run.
+fn select_gossip_port(ledger_path: &Path, bind_ip_addr: IpAddr) -> io::Result<u16> {
+    let gossip_port_file = ledger_path.join(GOSSIP_PORT_FILE);
+    let previous_port = fs::read_to_string(&gossip_port_file)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<u16>().ok())
+        .filter(|&port| port != 0);
+
+    let port = match previous_port {
+        Some(port)
+            if bind_common_with_config(bind_ip_addr, port, SocketConfiguration::default())
+                .is_ok() =>
+        {
+            port
+        }
+        _ => {
+            if let Some(port) = previous_port {
+                warn!("previous gossip port {port} is unavailable, selecting a new one");
+            }
+            bind_to(bind_ip_addr, 0)?.local_addr()?.port()
+        }
+    };
+    fs::write(&gossip_port_file, port.to_string())?;
+    Ok(port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_log_filter() {
+        assert_eq!(resolve_log_filter(None, None), DEFAULT_FILTER);
+        assert_eq!(resolve_log_filter(None, Some("warn")), "warn");
+        assert_eq!(resolve_log_filter(Some("debug"), None), "debug");
+        assert_eq!(resolve_log_filter(Some("debug"), Some("warn")), "debug");
+    }
+
+    #[test]
+    fn test_prepare_logfile() {
+        assert_eq!(prepare_logfile("-").unwrap(), None);
+
+        let tmp_dir = TempDir::new().unwrap();
+        let logfile = tmp_dir.path().join("logs").join("validator.log");
+        let prepared = prepare_logfile(logfile.to_str().unwrap()).unwrap().unwrap();
+        assert_eq!(
+            Path::new(&prepared),
+            fs::canonicalize(tmp_dir.path())
+                .unwrap()
+                .join("logs")
+                .join("validator.log")
+        );
+        assert!(Path::new(&prepared).is_file());
+
+        // The log directory can't be created under a regular file
+        let not_a_dir = tmp_dir.path().join("not-a-dir");
+        fs::write(&not_a_dir, "").unwrap();
+        let err = prepare_logfile(not_a_dir.join("validator.log").to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("unable to create log directory"));
+
+        // A directory is not a writable log file
+        let err = prepare_logfile(tmp_dir.path().join("logs").to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("is not writable"));
+    }
+
+    #[test]
+    fn test_resolve_rpc_bind_address() {
+        let ipv4 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let ipv6 = "2001:db8::1".parse().unwrap();
+        assert_eq!(
+            resolve_rpc_bind_address(None, true, ipv4),
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        );
+        assert_eq!(
+            resolve_rpc_bind_address(None, true, ipv6),
+            IpAddr::V6(Ipv6Addr::LOCALHOST)
+        );
+        assert_eq!(resolve_rpc_bind_address(None, false, ipv4), ipv4);
+        assert_eq!(resolve_rpc_bind_address(None, false, ipv6), ipv6);
+        // an explicit address wins
+        assert_eq!(resolve_rpc_bind_address(Some(ipv6), true, ipv4), ipv6);
+    }
+
+    #[test]
+    fn test_rpc_addrs() {
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        assert_eq!(rpc_addrs(ip_addr, None, None), Ok(None));
+        assert_eq!(
+            rpc_addrs(ip_addr, Some(8899), None),
+            Ok(Some((
+                SocketAddr::new(ip_addr, 8899),
+                SocketAddr::new(ip_addr, 8900)
+            )))
+        );
+        assert_eq!(
+            rpc_addrs(ip_addr, Some(8899), Some(9000)),
+            Ok(Some((
+                SocketAddr::new(ip_addr, 8899),
+                SocketAddr::new(ip_addr, 9000)
+            )))
+        );
+        assert!(rpc_addrs(ip_addr, Some(8899), Some(8899)).is_err());
+        assert!(rpc_addrs(ip_addr, Some(u16::MAX), None).is_err());
+        assert!(rpc_addrs(ip_addr, Some(u16::MAX), Some(9000)).is_ok());
+    }
+
+    #[test]
+    fn test_select_gossip_port_reuses_persisted_port() {
+        let ledger_path = TempDir::new().unwrap();
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let port = select_gossip_port(ledger_path.path(), ip_addr).unwrap();
+        assert_ne!(port, 0);
+        assert_eq!(
+            fs::read_to_string(ledger_path.path().join(GOSSIP_PORT_FILE)).unwrap(),
+            port.to_string()
+        );
+        assert_eq!(
+            select_gossip_port(ledger_path.path(), ip_addr).unwrap(),
+            port
+        );
+    }
+
+    #[test]
+    fn test_select_gossip_port_falls_back_when_taken() {
+        let ledger_path = TempDir::new().unwrap();
+        let ip_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let port = select_gossip_port(ledger_path.path(), ip_addr).unwrap();
+        // Another process grabs the port before the validator restarts
+        let _taken =
+            bind_common_with_config(ip_addr, port, SocketConfiguration::default()).unwrap();
+
+        let new_port = select_gossip_port(ledger_path.path(), ip_addr).unwrap();
+        assert_ne!(new_port, port);
+        assert_eq!(
+            fs::read_to_string(ledger_path.path().join(GOSSIP_PORT_FILE)).unwrap(),
+            new_port.to_string()
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::cli::DefaultArgs;
+use crate::commands::{required_arg, FromClapArgMatches};
+use blockchain_ledger::blockstore::Blockstore;
+use clap::{value_parser, Arg, ArgMatches, Command};
+use solana_clock::Slot;
+use std::error;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND: &str = "slot-meta";
+
+pub fn command(default_args: &DefaultArgs) -> Command {
+    Command::new(COMMAND)
+        .about("Print the slot meta and shred counts of a slot in the ledger")
+        .arg(
+            Arg::new("ledger_path")
+                .short('l')
+                .long("ledger")
+                .value_name("DIR")
+                .default_value(default_args.ledger_path)
+                .help("Use DIR as ledger location"),
+        )
+        .arg(
+            Arg::new("slot")
+                .long("slot")
+                .value_name("SLOT")
+                .required(true)
+                .value_parser(value_parser!(Slot))
+                .help("Slot to print"),
+        )
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SlotMetaArgs {
+    pub ledger_path: PathBuf,
+    pub slot: Slot,
+}
+
+impl FromClapArgMatches for SlotMetaArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> crate::commands::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(SlotMetaArgs {
+            ledger_path: required_arg::<String>(matches, "ledger_path", "--ledger is required")?
+                .into(),
+            slot: required_arg(matches, "slot", "--slot is required")?,
+        })
+    }
+}
+
+/// Opens the blockstore with secondary access and prints the slot meta and the
+/// number of data and coding shreds of the slot.
+pub fn execute(matches: &ArgMatches, out: &mut impl Write) -> Result<(), Box<dyn error::Error>> {
+    let args = SlotMetaArgs::from_clap_arg_match(matches)?;
+    let slot = args.slot;
+
+    let blockstore = Blockstore::open_secondary(&args.ledger_path)?;
+    let Some(slot_meta) = blockstore.meta(slot)? else {
+        writeln!(out, "Slot {slot} not found in the ledger")?;
+        return Ok(());
+    };
+
+    writeln!(out, "Slot: {slot}")?;
+    writeln!(out, "Consumed: {}", slot_meta.consumed)?;
+    writeln!(out, "Received: {}", slot_meta.received)?;
+    writeln!(out, "Last index: {:?}", slot_meta.last_index)?;
+    writeln!(out, "Parent slot: {:?}", slot_meta.parent_slot)?;
+    writeln!(out, "Children: {:?}", slot_meta.children())?;
+    let (num_data, num_coding) = blockstore
+        .get_index(slot)?
+        .map(|index| (index.data().num_shreds(), index.coding().num_shreds()))
+        .unwrap_or_default();
+    writeln!(out, "Data shreds: {num_data}")?;
+    writeln!(out, "Coding shreds: {num_coding}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::subcommand_matches;
+    use blockchain_ledger::blockstore_meta::{Index, SlotMeta};
+    use tempfile::TempDir;
+
+    fn print_slot_meta(ledger_path: &TempDir, slot: Slot) -> String {
+        let mut out = Vec::new();
+        execute(
+            &subcommand_matches(
+                COMMAND,
+                &[
+                    "--ledger",
+                    ledger_path.path().to_str().unwrap(),
+                    "--slot",
+                    &slot.to_string(),
+                ],
+            ),
+            &mut out,
+        )
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_slot_meta() {
+        let ledger_path = TempDir::new().unwrap();
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            let mut slot_meta = SlotMeta {
+                slot: 5,
+                consumed: 2,
+                received: 3,
+                last_index: Some(2),
+                parent_slot: Some(4),
+                ..SlotMeta::default()
+            };
+            slot_meta.add_next_slot(6);
+            blockstore.put_meta(5, &slot_meta).unwrap();
+            let mut index = Index::new(5);
+            for i in 0..3 {
+                index.data_mut().insert(i);
+            }
+            index.coding_mut().insert(0);
+            blockstore.put_index(5, &index).unwrap();
+        }
+
+        assert_eq!(
+            print_slot_meta(&ledger_path, 5),
+            "Slot: 5\n\
+             Consumed: 2\n\
+             Received: 3\n\
+             Last index: Some(2)\n\
+             Parent slot: Some(4)\n\
+             Children: [6]\n\
+             Data shreds: 3\n\
+             Coding shreds: 1\n"
+        );
+        assert_eq!(
+            print_slot_meta(&ledger_path, 7),
+            "Slot 7 not found in the ledger\n"
+        );
+    }
+}
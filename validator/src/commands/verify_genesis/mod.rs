@@ -0,0 +1,178 @@
+use crate::cli::{parse_hash_validator, DefaultArgs};
+use crate::commands::{required_arg, FromClapArgMatches};
+use blockchain_accounts_db::hardened_unpack::{
+    open_genesis_config, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+};
+use blockchain_core::validator::ValidatorError;
+use blockchain_ledger::shred::compute_shred_version;
+use blockchain_runtime::genesis_utils::genesis_feature_set;
+use clap::{Arg, ArgMatches, Command};
+use solana_hash::Hash;
+use std::error;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const COMMAND: &str = "verify-genesis";
+
+pub fn command(default_args: &DefaultArgs) -> Command {
+    Command::new(COMMAND)
+        .about("Check that the genesis config of a ledger has the expected hash")
+        .arg(
+            Arg::new("ledger_path")
+                .short('l')
+                .long("ledger")
+                .value_name("DIR")
+                .default_value(default_args.ledger_path)
+                .help("Use DIR as ledger location"),
+        )
+        .arg(
+            Arg::new("expected_genesis_hash")
+                .long("expected-genesis-hash")
+                .value_name("HASH")
+                .required(true)
+                .value_parser(parse_hash_validator)
+                .help("Hash the genesis config is expected to have"),
+        )
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyGenesisArgs {
+    pub ledger_path: PathBuf,
+    pub expected_genesis_hash: Hash,
+}
+
+impl FromClapArgMatches for VerifyGenesisArgs {
+    fn from_clap_arg_match(matches: &ArgMatches) -> crate::commands::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(VerifyGenesisArgs {
+            ledger_path: required_arg::<String>(matches, "ledger_path", "--ledger is required")?
+                .into(),
+            expected_genesis_hash: required_arg(
+                matches,
+                "expected_genesis_hash",
+                "--expected-genesis-hash is required",
+            )?,
+        })
+    }
+}
+
+/// Loads the genesis config of the ledger, fails if its hash isn't the
+/// expected one and prints a summary of it otherwise.
+pub fn execute(matches: &ArgMatches, out: &mut impl Write) -> Result<(), Box<dyn error::Error>> {
+    let args = VerifyGenesisArgs::from_clap_arg_match(matches)?;
+
+    let genesis_config = open_genesis_config(&args.ledger_path, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE)
+        .map_err(ValidatorError::OpenGenesisConfig)?;
+    let genesis_hash = genesis_config.hash();
+    if genesis_hash != args.expected_genesis_hash {
+        return Err(
+            ValidatorError::GenesisHashMismatch(genesis_hash, args.expected_genesis_hash).into(),
+        );
+    }
+
+    writeln!(out, "Genesis hash: {genesis_hash}")?;
+    writeln!(out, "Cluster type: {:?}", genesis_config.cluster_type)?;
+    writeln!(out, "Ticks per slot: {}", genesis_config.ticks_per_slot)?;
+    // The genesis config carries no hard forks, so only its features are mixed
+    // into the shred version
+    let feature_set = genesis_feature_set(&genesis_config);
+    writeln!(
+        out,
+        "Shred version: {}",
+        compute_shred_version(&genesis_hash, Some(&feature_set))
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::subcommand_matches;
+    use blockchain_runtime::genesis_utils::activate_all_features;
+    use solana_genesis_config::GenesisConfig;
+    use tempfile::TempDir;
+
+    fn verify_genesis(genesis_config: &GenesisConfig) -> String {
+        let ledger_path = TempDir::new().unwrap();
+        genesis_config.write(ledger_path.path()).unwrap();
+
+        let mut out = Vec::new();
+        execute(
+            &subcommand_matches(
+                COMMAND,
+                &[
+                    "--ledger",
+                    ledger_path.path().to_str().unwrap(),
+                    "--expected-genesis-hash",
+                    &genesis_config.hash().to_string(),
+                ],
+            ),
+            &mut out,
+        )
+        .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_verify_genesis() {
+        let genesis_config = GenesisConfig {
+            ticks_per_slot: 16,
+            ..GenesisConfig::default()
+        };
+        let genesis_hash = genesis_config.hash();
+
+        let out = verify_genesis(&genesis_config);
+        assert!(out.contains(&format!("Genesis hash: {genesis_hash}")));
+        assert!(out.contains("Cluster type: Development"));
+        assert!(out.contains("Ticks per slot: 16"));
+        assert!(out.contains(&format!(
+            "Shred version: {}",
+            compute_shred_version(&genesis_hash, None)
+        )));
+    }
+
+    #[test]
+    fn test_verify_genesis_shred_version_with_features() {
+        let mut genesis_config = GenesisConfig::default();
+        activate_all_features(&mut genesis_config);
+        let genesis_hash = genesis_config.hash();
+        let feature_set = genesis_feature_set(&genesis_config);
+        assert!(!feature_set.active().is_empty());
+
+        let shred_version = compute_shred_version(&genesis_hash, Some(&feature_set));
+        assert_ne!(shred_version, compute_shred_version(&genesis_hash, None));
+        let out = verify_genesis(&genesis_config);
+        assert!(out.contains(&format!("Shred version: {shred_version}")));
+    }
+
+    #[test]
+    fn test_verify_genesis_mismatch() {
+        let ledger_path = TempDir::new().unwrap();
+        let genesis_config = GenesisConfig::default();
+        genesis_config.write(ledger_path.path()).unwrap();
+        let expected_genesis_hash = Hash::new_unique();
+
+        let mut out = Vec::new();
+        let err = execute(
+            &subcommand_matches(
+                COMMAND,
+                &[
+                    "--ledger",
+                    ledger_path.path().to_str().unwrap(),
+                    "--expected-genesis-hash",
+                    &expected_genesis_hash.to_string(),
+                ],
+            ),
+            &mut out,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ValidatorError>(),
+            Some(ValidatorError::GenesisHashMismatch(actual, expected))
+                if *actual == genesis_config.hash() && *expected == expected_genesis_hash
+        ));
+        assert!(out.is_empty());
+    }
+}
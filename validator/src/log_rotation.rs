@@ -0,0 +1,114 @@
+//! Size-based rotation of the validator log file.
+//!
+//! `solana_logger::redirect_stderr_to_file` reopens the log file on SIGUSR1,
+//! which lets external tools like logrotate move the file aside. This module
+//! does the same from inside the process: once the file grows past the
+//! configured size it is renamed to `<logfile>.1` (replacing any previous
+//! rotation) and SIGUSR1 is raised so the logger reopens a fresh file. There
+//! is no SIGUSR1 outside of unix, so rotation is only supported there.
+
+#[cfg(unix)]
+use log::{info, warn};
+#[cfg(unix)]
+use nix::sys::signal::{raise, Signal};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::thread;
+use std::thread::JoinHandle;
+#[cfg(unix)]
+use std::time::Duration;
+
+/// How often the log file size is checked.
+#[cfg(unix)]
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Path the log file is moved to when it is rotated.
+pub fn rotated_path(logfile: &Path) -> PathBuf {
+    let mut rotated = logfile.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Renames `logfile` to [`rotated_path`] if it is larger than `max_size` bytes.
+///
+/// Returns whether the file was rotated. A missing log file is not an error.
+pub fn rotate_if_needed(logfile: &Path, max_size: u64) -> io::Result<bool> {
+    let size = match fs::metadata(logfile) {
+        Ok(metadata) => metadata.len(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err),
+    };
+    if size <= max_size {
+        return Ok(false);
+    }
+    fs::rename(logfile, rotated_path(logfile))?;
+    Ok(true)
+}
+
+/// Spawns a thread that rotates `logfile` whenever it exceeds `max_size` bytes.
+///
+/// Must be called after `redirect_stderr_to_file`, which installs the SIGUSR1
+/// handler used to reopen the log file.
+#[cfg(unix)]
+pub fn spawn(logfile: String, max_size: u64) -> io::Result<JoinHandle<()>> {
+    thread::Builder::new()
+        .name("solLogRotate".into())
+        .spawn(move || loop {
+            thread::sleep(CHECK_INTERVAL);
+            match rotate_if_needed(Path::new(&logfile), max_size) {
+                Ok(true) => {
+                    if let Err(err) = raise(Signal::SIGUSR1) {
+                        warn!("unable to reopen log file {logfile} after rotation: {err}");
+                    } else {
+                        info!("rotated log file {logfile}");
+                    }
+                }
+                Ok(false) => {}
+                Err(err) => warn!("unable to rotate log file {logfile}: {err}"),
+            }
+        })
+}
+
+/// Log rotation relies on SIGUSR1 to reopen the log file, which only exists on
+/// unix.
+#[cfg(not(unix))]
+pub fn spawn(_logfile: String, _max_size: u64) -> io::Result<JoinHandle<()>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "log rotation is only supported on unix",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_rotate_if_needed() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let logfile = tmp_dir.path().join("validator.log");
+        let max_size = 16;
+
+        // missing file
+        assert!(!rotate_if_needed(&logfile, max_size).unwrap());
+
+        let mut file = fs::File::create(&logfile).unwrap();
+        file.write_all(&[b'a'; 16]).unwrap();
+        assert!(!rotate_if_needed(&logfile, max_size).unwrap());
+        assert!(!rotated_path(&logfile).exists());
+
+        // writing past the threshold triggers a rotation
+        file.write_all(b"b").unwrap();
+        assert!(rotate_if_needed(&logfile, max_size).unwrap());
+        assert!(!logfile.exists());
+        assert_eq!(fs::read(rotated_path(&logfile)).unwrap().len(), 17);
+
+        // a second rotation replaces the previous one
+        fs::write(&logfile, [b'c'; 32]).unwrap();
+        assert!(rotate_if_needed(&logfile, max_size).unwrap());
+        assert_eq!(fs::read(rotated_path(&logfile)).unwrap(), [b'c'; 32]);
+    }
+}
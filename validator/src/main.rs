@@ -1,10 +1,12 @@
 use crate::cli::{command, DefaultArgs};
 use std::error;
+use std::io;
 use std::path::PathBuf;
 
 mod bootstrap;
 mod cli;
 mod commands;
+mod log_rotation;
 
 fn main() -> Result<(), Box<dyn error::Error>> {
     let default_args = DefaultArgs::new();
@@ -12,7 +14,20 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         .try_get_matches()
         .unwrap_or_else(|e| e.exit());
 
-    let ledger_path = PathBuf::from(matches.try_get_one::<String>("ledger_path")?.unwrap());
-
-    commands::run::execute(&matches, &ledger_path)
+    match matches.subcommand() {
+        Some((commands::init::COMMAND, matches)) => commands::init::execute(matches),
+        Some((commands::print_config::COMMAND, matches)) => {
+            commands::print_config::execute(matches, &mut io::stdout())
+        }
+        Some((commands::slot_meta::COMMAND, matches)) => {
+            commands::slot_meta::execute(matches, &mut io::stdout())
+        }
+        Some((commands::verify_genesis::COMMAND, matches)) => {
+            commands::verify_genesis::execute(matches, &mut io::stdout())
+        }
+        _ => {
+            let ledger_path = PathBuf::from(matches.try_get_one::<String>("ledger_path")?.unwrap());
+            commands::run::execute(&matches, &ledger_path)
+        }
+    }
 }